@@ -0,0 +1,64 @@
+//! Responses of the api cached in `<polkahub home>/cache`
+use anyhow::Result;
+use serde_derive::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, time::Duration};
+
+use crate::parsing::polkahub_home_path;
+
+fn entry_path(key: &str) -> PathBuf {
+    let file_name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    polkahub_home_path().join("cache").join(file_name)
+}
+
+/// cached data of `key` if it is younger than `ttl`
+pub fn read(key: &str, ttl: Duration) -> Option<String> {
+    let path = entry_path(key);
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > ttl {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+pub fn write(key: &str, data: &str) -> Result<()> {
+    let path = entry_path(key);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// cached response with the ETag to revalidate it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub etag: Option<String>,
+    pub body: String,
+}
+
+pub fn load(key: &str) -> Option<Entry> {
+    let data = fs::read_to_string(entry_path(key)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+pub fn store(key: &str, entry: &Entry) -> Result<()> {
+    write(key, &serde_json::to_string(entry)?)
+}
+
+/// remove all cached responses
+pub fn clear() -> Result<()> {
+    let dir = polkahub_home_path().join("cache");
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,222 @@
+//! Shell completion scripts generated from the StructOpt definition.
+//! Project references are completed by the hidden `__complete <prefix>` action.
+use anyhow::Result;
+use std::{io::Write, str::FromStr};
+use structopt::{clap::Shell, StructOpt};
+
+use crate::{
+    error::PolkahubError,
+    parsing::{Project, ACTIONS},
+};
+
+const BIN_NAME: &str = "polkahub";
+/// hidden action printing project references
+const COMPLETE_ACTION: &str = "__complete";
+
+/// bash splits words on `@`, so the reply drops everything up to it.
+/// Generated bash script does not complete values of positional arguments.
+const BASH_PROJECTS: &str = r#"
+_polkahub_projects() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local word="${line##* }"
+    if [[ $COMP_CWORD -eq 1 && "$word" != -* ]]; then
+        COMPREPLY=($(compgen -W "@ACTIONS@" -- "$word"))
+        return 0
+    fi
+    if [[ "$word" == */* ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(polkahub __complete "$word" 2>/dev/null)" -- "$word"))
+        if [[ "$word" == *@* ]]; then
+            COMPREPLY=("${COMPREPLY[@]#*@}")
+        fi
+        return 0
+    fi
+    _polkahub "$@"
+}
+
+complete -F _polkahub_projects -o bashdefault -o default polkahub
+"#;
+
+const ZSH_PROJECTS: &str = r#"
+_polkahub_projects() {
+    if [[ "$PREFIX" == */* ]]; then
+        local -a projects
+        projects=(${(f)"$(polkahub __complete "$PREFIX" 2>/dev/null)"})
+        compadd -a projects
+    else
+        _polkahub "$@"
+    fi
+}
+
+_polkahub_projects "$@""#;
+
+const FISH_PROJECTS: &str = r#"
+complete -c polkahub -f -n '__fish_use_subcommand' -a '@ACTIONS@'
+complete -c polkahub -f -n 'string match -q "*/*" -- (commandline -ct)' -a '(polkahub __complete (commandline -ct) 2>/dev/null)'
+"#;
+
+/// print completion script for `shell`: bash, zsh or fish
+pub fn generate<W: Write>(shell: &str, out: &mut W) -> Result<()> {
+    let target = match shell {
+        "bash" | "zsh" | "fish" => Shell::from_str(shell).map_err(PolkahubError::Input)?,
+        _ => return Err(PolkahubError::Input(
+            "You must choose a shell: `completions bash`, `completions zsh` or `completions fish`"
+                .into(),
+        )
+        .into()),
+    };
+    let mut script = vec![];
+    Project::clap().gen_completions_to(BIN_NAME, target, &mut script);
+    let actions: Vec<&str> = ACTIONS
+        .iter()
+        .cloned()
+        .filter(|a| *a != COMPLETE_ACTION)
+        .collect();
+    let actions = actions.join(" ");
+    let script = String::from_utf8_lossy(&script);
+    match target {
+        Shell::Bash => write!(
+            out,
+            "{}{}",
+            script,
+            BASH_PROJECTS.replace("@ACTIONS@", &actions)
+        )?,
+        // zsh script ends with the call of the generated function, wrap it instead
+        Shell::Zsh => write!(
+            out,
+            "{}{}",
+            zsh_actions(&script, &actions)
+                .trim_end()
+                .trim_end_matches("_polkahub \"$@\""),
+            ZSH_PROJECTS
+        )?,
+        _ => write!(
+            out,
+            "{}{}",
+            script,
+            FISH_PROJECTS.replace("@ACTIONS@", &actions)
+        )?,
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// `action` takes any string to report typos as invalid actions,
+/// so its spec completes files until the actions are put in place
+fn zsh_actions(script: &str, actions: &str) -> String {
+    script
+        .lines()
+        .map(|line| {
+            if line.starts_with("':action -- ") {
+                line.replacen(":_files'", &format!(":({})'", actions), 1)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `login/name` and `login/name@version` references matching `prefix`
+pub fn candidates<'a, I>(prefix: &str, versions: I) -> Vec<String>
+where
+    I: IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+{
+    let mut refs = vec![];
+    for (login, name, version) in versions {
+        let project = format!("{}/{}", login, name);
+        if !refs.contains(&project) {
+            refs.push(project.clone());
+        }
+        refs.push(format!("{}@{}", project, version));
+    }
+    refs.retain(|r| r.starts_with(prefix));
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(shell: &str) -> String {
+        let mut out = vec![];
+        generate(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// the hidden action is only called by the wrappers, never offered
+    fn assert_complete_hidden(script: &str) {
+        for line in script.lines().filter(|l| l.contains(COMPLETE_ACTION)) {
+            assert!(
+                line.contains("polkahub __complete "),
+                "offered in: {}",
+                line
+            );
+        }
+        assert!(!script.contains("@ACTIONS@"));
+    }
+
+    #[test]
+    fn bash_script() {
+        let script = script("bash");
+        assert_complete_hidden(&script);
+        assert!(script.contains("compgen -W \"create find help install"));
+        assert!(
+            script.contains("complete -F _polkahub_projects -o bashdefault -o default polkahub")
+        );
+    }
+
+    #[test]
+    fn zsh_script() {
+        let script = script("zsh");
+        assert_complete_hidden(&script);
+        assert!(script.contains(":(create find help install"));
+        assert!(!script
+            .contains(":action -- create <name>, find <name>, install <name> <version>:_files"));
+        assert!(script.trim_end().ends_with("_polkahub_projects \"$@\""));
+        assert_eq!(script.matches("\n_polkahub \"$@\"").count(), 0);
+    }
+
+    #[test]
+    fn fish_script() {
+        let script = script("fish");
+        assert_complete_hidden(&script);
+        assert!(script.contains("-a 'create find help install"));
+        assert!(script.contains("-a '(polkahub __complete (commandline -ct) 2>/dev/null)'"));
+    }
+
+    #[test]
+    fn unknown_shell() {
+        let e = generate("powershell", &mut vec![]).unwrap_err();
+        match e.downcast::<PolkahubError>().unwrap() {
+            PolkahubError::Input(message) => assert!(message.contains("choose a shell")),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    const VERSIONS: &[(&str, &str, &str)] = &[
+        ("alice", "myapp", "1.0.0"),
+        ("alice", "myapp", "1.1.0"),
+        ("alice", "mynode", "0.1.0"),
+        ("bob", "myapp", "2.0.0"),
+    ];
+
+    #[test]
+    fn project_candidates() {
+        assert_eq!(
+            candidates("alice/my", VERSIONS.iter().cloned()),
+            [
+                "alice/myapp",
+                "alice/myapp@1.0.0",
+                "alice/myapp@1.1.0",
+                "alice/mynode",
+                "alice/mynode@0.1.0",
+            ]
+        );
+        assert_eq!(
+            candidates("alice/myapp@1.1", VERSIONS.iter().cloned()),
+            ["alice/myapp@1.1.0"]
+        );
+        assert!(candidates("carol/", VERSIONS.iter().cloned()).is_empty());
+    }
+}
@@ -0,0 +1,360 @@
+//! In-memory mock of the Polkahub api for local testing.
+//! Point the cli to it with `POLKAHUB_API_URL=http://<addr>` and a separate `POLKAHUB_HOME`.
+use anyhow::Result;
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde_derive::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    env,
+    future::Future,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+const DEFAULT_LIMIT: u64 = 20;
+
+#[derive(Debug, Clone, Serialize)]
+struct Version {
+    login: String,
+    name: String,
+    version: String,
+    description: Option<String>,
+    yanked: bool,
+    deprecated: Option<String>,
+    published_at: Option<String>,
+    chainspec_hash: Option<String>,
+}
+
+#[derive(Debug)]
+struct DevProject {
+    login: String,
+    /// login of the user who created the project, differs from `login` for org projects
+    creator: String,
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// email -> password
+    users: HashMap<String, String>,
+    /// token -> login
+    tokens: HashMap<String, String>,
+    projects: Vec<DevProject>,
+    versions: Vec<Version>,
+    /// hash -> uploaded chainspec
+    chainspecs: HashMap<String, Vec<u8>>,
+    /// app name -> installed version
+    apps: HashMap<String, Version>,
+}
+
+fn field<'a>(body: &'a Value, name: &str) -> Result<&'a str, String> {
+    match body[name].as_str() {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(format!("{} is required", name)),
+    }
+}
+
+/// login is the local part of the email
+fn login_of(email: &str) -> String {
+    email.split('@').next().unwrap_or(email).to_lowercase()
+}
+
+impl State {
+    fn authorize(&self, token: Option<&str>) -> Result<String, String> {
+        token
+            .and_then(|t| self.tokens.get(t))
+            .cloned()
+            .ok_or_else(|| "invalid token".to_string())
+    }
+
+    fn signup(&mut self, body: &Value) -> Result<Value, String> {
+        let (email, password) = (field(body, "email")?, field(body, "password")?);
+        if self.users.contains_key(email) {
+            return Err(format!("user {} already exists", email));
+        }
+        self.users.insert(email.into(), password.into());
+        Ok(Value::Null)
+    }
+
+    fn login(&mut self, body: &Value) -> Result<Value, String> {
+        let (email, password) = (field(body, "email")?, field(body, "password")?);
+        if self.users.get(email).map(String::as_str) != Some(password) {
+            return Err("invalid email or password".into());
+        }
+        let token = format!("dev-token-{}", self.tokens.len() + 1);
+        self.tokens.insert(token.clone(), login_of(email));
+        Ok(json!({ "token": token }))
+    }
+
+    fn create(&mut self, login: String, body: &Value) -> Result<Value, String> {
+        let name = field(body, "project_name")?;
+        let owner = body["org"].as_str().map_or(login.clone(), String::from);
+        if self
+            .projects
+            .iter()
+            .any(|p| p.login == owner && p.name == name)
+        {
+            return Err(format!("project {}/{} already exists", owner, name));
+        }
+        self.projects.push(DevProject {
+            login: owner.clone(),
+            creator: login,
+            name: name.into(),
+            description: body["description"].as_str().map(String::from),
+        });
+        Ok(json!({
+            "repo_url": format!("git@dev.polkahub.local:{}/{}.git", owner, name),
+            "http_url": format!("http://{}.dev.polkahub.local", name),
+            "ws_url": format!("ws://{}.dev.polkahub.local", name),
+            "repository_created": true,
+        }))
+    }
+
+    fn upload_chainspec(&mut self, chainspec: &[u8]) -> Result<Value, String> {
+        if serde_json::from_slice::<Value>(chainspec).is_err() {
+            return Err("chainspec is not valid json".into());
+        }
+        let hash: String = Sha256::digest(chainspec)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.chainspecs.insert(hash.clone(), chainspec.to_vec());
+        Ok(json!({ "hash": hash }))
+    }
+
+    /// organizations are not modelled, members are not checked for `org`
+    fn publish(&mut self, login: String, body: &Value) -> Result<Value, String> {
+        let (name, version) = (field(body, "project_name")?, field(body, "version")?);
+        let owner = body["org"].as_str().map(String::from).unwrap_or(login);
+        let project = match self
+            .projects
+            .iter()
+            .find(|p| p.login == owner && p.name == name)
+        {
+            Some(p) => p,
+            None => return Err(format!("project {}/{} not found", owner, name)),
+        };
+        let chainspec_hash = body["chainspec_hash"].as_str();
+        if let Some(hash) = chainspec_hash {
+            if !self.chainspecs.contains_key(hash) {
+                return Err(format!("chainspec {} is not uploaded", hash));
+            }
+        }
+        if self
+            .versions
+            .iter()
+            .any(|v| v.login == owner && v.name == name && v.version == version)
+        {
+            return Err(format!(
+                "version {} of {}/{} already exists",
+                version, owner, name
+            ));
+        }
+        self.versions.push(Version {
+            login: owner.clone(),
+            name: name.into(),
+            version: version.into(),
+            description: body["description"]
+                .as_str()
+                .map(String::from)
+                .or_else(|| project.description.clone()),
+            yanked: false,
+            deprecated: None,
+            published_at: None,
+            chainspec_hash: chainspec_hash.map(String::from),
+        });
+        Ok(json!({ "login": owner, "name": name, "version": version }))
+    }
+
+    fn find(&self, body: &Value) -> Result<Value, String> {
+        let name = body["name"].as_str().unwrap_or("");
+        let user = body["user"].as_str();
+        let limit = body["limit"].as_u64().unwrap_or(DEFAULT_LIMIT) as usize;
+        let page = body["page"].as_u64().unwrap_or(1).max(1) as usize;
+        let found: Vec<&Version> = self
+            .versions
+            .iter()
+            .filter(|v| v.name.contains(name))
+            .filter(|v| user.is_none() || user == Some(v.login.as_str()))
+            .skip((page - 1) * limit)
+            .take(limit)
+            .collect();
+        Ok(json!(found))
+    }
+
+    /// projects of `org` created by `login`, or owned by `login` without `org`,
+    /// with their versions, and all installed apps
+    fn list(&self, login: &str, body: &Value) -> Result<Value, String> {
+        let org = body["org"].as_str();
+        let projects: Vec<Value> = self
+            .projects
+            .iter()
+            .filter(|p| match org {
+                Some(org) => p.login == org && p.creator == login,
+                None => p.login == login,
+            })
+            .map(|p| {
+                let versions: Vec<&str> = self
+                    .versions
+                    .iter()
+                    .filter(|v| v.login == p.login && v.name == p.name)
+                    .map(|v| v.version.as_str())
+                    .collect();
+                json!({
+                    "login": p.login,
+                    "name": p.name,
+                    "versions": versions,
+                    "description": p.description,
+                })
+            })
+            .collect();
+        let apps: Vec<Value> = self
+            .apps
+            .iter()
+            .map(|(app, v)| {
+                json!({
+                    "app_name": app,
+                    "project_name": v.name,
+                    "version": v.version,
+                    "http_url": format!("http://{}.dev.polkahub.local", app),
+                    "ws_url": format!("ws://{}.dev.polkahub.local", app),
+                })
+            })
+            .collect();
+        Ok(json!({ "projects": projects, "apps": apps }))
+    }
+
+    fn install(&mut self, body: &Value) -> Result<Value, String> {
+        let app = field(body, "app_name")?;
+        let (login, name, version) = (
+            field(body, "login")?,
+            field(body, "project_name")?,
+            field(body, "version")?,
+        );
+        let installed = match self
+            .versions
+            .iter()
+            .find(|v| v.login == login && v.name == name && v.version == version)
+        {
+            Some(v) => v.clone(),
+            None => return Err(format!("{}/{}@{} not found", login, name, version)),
+        };
+        self.apps.insert(app.into(), installed);
+        Ok(json!({
+            "http_url": format!("http://{}.dev.polkahub.local", app),
+            "ws_url": format!("ws://{}.dev.polkahub.local", app),
+        }))
+    }
+
+    fn route(
+        &mut self,
+        path: &str,
+        token: Option<&str>,
+        bytes: &[u8],
+    ) -> Option<Result<Value, String>> {
+        let body = &serde_json::from_slice(bytes).unwrap_or(Value::Null);
+        let reply = match path {
+            "/signup" => self.signup(body),
+            "/login" => self.login(body),
+            "/find" => self.authorize(token).and_then(|_| self.find(body)),
+            "/projects" => self
+                .authorize(token)
+                .and_then(|login| self.create(login, body)),
+            "/publish" => self
+                .authorize(token)
+                .and_then(|login| self.publish(login, body)),
+            "/list" => self
+                .authorize(token)
+                .and_then(|login| self.list(&login, body)),
+            "/install" => self.authorize(token).and_then(|_| self.install(body)),
+            "/chainspecs" => self
+                .authorize(token)
+                .and_then(|_| self.upload_chainspec(bytes)),
+            _ => return None,
+        };
+        Some(reply)
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let path = request
+        .uri()
+        .path()
+        .trim_start_matches("/api/v1")
+        .to_string();
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim_start_matches("Bearer ").to_string());
+    let bytes = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+
+    let reply =
+        state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .route(&path, token.as_deref(), &bytes);
+    let (status, envelope) = match reply {
+        Some(Ok(Value::Null)) => (StatusCode::OK, json!({ "status": "ok" })),
+        Some(Ok(payload)) => (
+            StatusCode::OK,
+            json!({ "status": "ok", "payload": payload }),
+        ),
+        Some(Err(reason)) => (
+            StatusCode::OK,
+            json!({ "status": "error", "reason": reason }),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            json!({ "status": "error", "reason": format!("{} is not served by dev-server", path) }),
+        ),
+    };
+    println!("{} {}", status.as_u16(), path);
+
+    let mut response = Response::new(Body::from(envelope.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    Ok(response)
+}
+
+/// bind the api to `addr`, returns the bound address and the server to run
+pub fn serve(addr: SocketAddr) -> Result<(SocketAddr, impl Future<Output = hyper::Result<()>>)> {
+    let state = Arc::new(Mutex::new(State::default()));
+    let make_service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+    });
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    Ok((server.local_addr(), server))
+}
+
+/// serve the api until the process is stopped
+pub async fn run(addr: SocketAddr) -> Result<()> {
+    let (addr, server) = serve(addr)?;
+    // dev tokens must not replace the login to the real api
+    let home = env::temp_dir().join("polkahub-dev");
+    println!("Polkahub dev-server listening on http://{}", addr);
+    println!(
+        "Run `export POLKAHUB_API_URL=http://{} POLKAHUB_HOME={}` to use it",
+        addr,
+        home.display()
+    );
+    server.await?;
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Errors shown to the user, rendered once on stderr by `main`
+use std::{fmt, io};
+use termion::color;
+
+use crate::parsing::{stderr_colors_enabled, HELP_NOTION};
+
+#[derive(Debug)]
+pub enum PolkahubError {
+    /// wrong arguments or answers to prompts
+    Input(String),
+    /// missing, invalid or expired token
+    Auth(String),
+    /// request did not reach Polkahub or the node
+    Network(String),
+    /// Polkahub refused to perform the action
+    Server { context: String, reason: String },
+    /// local files: Hub.toml, polkahub config, git repository
+    Config(String),
+    /// project or version does not exist
+    NotFound { context: String, reason: String },
+    /// no installed app with the name
+    AppNotFound { context: String, reason: String },
+}
+
+impl PolkahubError {
+    /// refusal with the `reason` of an `{"status": "error"}` response
+    pub fn server<R: ToString>(context: &str, reason: R) -> PolkahubError {
+        PolkahubError::Server {
+            context: context.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self {
+            PolkahubError::Input(_) => "Input error",
+            PolkahubError::Auth(_) => "Authentication error",
+            PolkahubError::Network(_) => "Network error",
+            PolkahubError::Server { context, .. } => context,
+            PolkahubError::Config(_) => "Configuration error",
+            PolkahubError::NotFound { context, .. }
+            | PolkahubError::AppNotFound { context, .. } => context,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            PolkahubError::Input(m)
+            | PolkahubError::Auth(m)
+            | PolkahubError::Network(m)
+            | PolkahubError::Config(m) => m.clone(),
+            PolkahubError::Server { reason, .. }
+            | PolkahubError::NotFound { reason, .. }
+            | PolkahubError::AppNotFound { reason, .. } => {
+                format!("Reason: {}", reason)
+            }
+        }
+    }
+
+    fn hint(&self) -> &str {
+        match self {
+            PolkahubError::Input(_) => HELP_NOTION,
+            PolkahubError::Auth(_) => "run `polkahub auth` or set POLKAHUB_TOKEN",
+            PolkahubError::Network(_) => "check your connection, rerun with -v to see the requests",
+            PolkahubError::Server { .. } => "rerun with -vv to see the full server response",
+            PolkahubError::Config(_) => "check Hub.toml and ~/.polkahub/config",
+            PolkahubError::NotFound { .. } => "check the name with `polkahub find`",
+            PolkahubError::AppNotFound { .. } => "check the app name with `polkahub list`",
+        }
+    }
+
+    /// framed title, message and hint on stderr
+    pub fn print(&self) {
+        let title = self.title();
+        let frame: String = title.chars().map(|_| '—').collect();
+        eprintln!(" {}", frame);
+        if stderr_colors_enabled() {
+            let red = color::Fg(color::Red);
+            eprintln!(" {}{}{}", red, title, color::Fg(color::Reset));
+        } else {
+            eprintln!(" {}", title);
+        }
+        eprintln!(" {}", frame);
+        eprintln!("{}", self.message());
+        eprintln!("hint: {}", self.hint());
+    }
+}
+
+impl fmt::Display for PolkahubError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}. {}", self.title(), self.message())
+    }
+}
+
+impl std::error::Error for PolkahubError {}
+
+/// sort errors of libraries into user facing kinds
+impl From<anyhow::Error> for PolkahubError {
+    fn from(e: anyhow::Error) -> PolkahubError {
+        let e = match e.downcast::<PolkahubError>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        let message = e.to_string();
+        if e.is::<reqwest::Error>() {
+            PolkahubError::Network(message)
+        } else if e.is::<serde_json::Error>() {
+            PolkahubError::server("Unexpected response", message)
+        } else if let Some(e) = e.downcast_ref::<io::Error>() {
+            match e.kind() {
+                io::ErrorKind::NotFound => PolkahubError::Config(message),
+                io::ErrorKind::PermissionDenied => PolkahubError::Config(message),
+                _ => PolkahubError::Input(message),
+            }
+        } else if e.is::<toml::de::Error>() || e.is::<git2::Error>() {
+            PolkahubError::Config(message)
+        } else {
+            PolkahubError::Input(message)
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use git2::{Cred, CredentialType, PushOptions, RemoteCallbacks, Repository};
+
+use crate::error::PolkahubError;
+
+/// Tag HEAD of the git repository in current directory as `v<version>`.
+/// Falls back to lightweight tag if no git identity is configured.
+pub fn tag_version(version: &str) -> Result<String> {
+    let repo = Repository::discover(".")?;
+    let head = repo.head()?.peel_to_commit()?;
+    let name = format!("v{}", version);
+    match repo.signature() {
+        Ok(signature) => repo.tag(
+            &name,
+            head.as_object(),
+            &signature,
+            &format!("polkahub version {}", version),
+            false,
+        )?,
+        Err(_) => repo.tag_lightweight(&name, head.as_object(), false)?,
+    };
+    Ok(name)
+}
+
+/// Name of the remote pointing to polkahub repository
+pub const REMOTE_NAME: &str = "polkahub";
+
+pub enum RemoteStatus {
+    Added,
+    /// remote with the same name is already configured, with the returned url
+    Exists(String),
+}
+
+/// Add `polkahub` remote to the git repository in current directory.
+pub fn add_remote(url: &str) -> Result<RemoteStatus> {
+    let repo = Repository::discover(".")?;
+    if let Ok(remote) = repo.find_remote(REMOTE_NAME) {
+        let existing = remote.url().unwrap_or("").to_string();
+        return Ok(RemoteStatus::Exists(existing));
+    }
+    repo.remote(REMOTE_NAME, url)?;
+    Ok(RemoteStatus::Added)
+}
+
+/// Push currently checked out branch to `polkahub` remote, returns branch name.
+/// Credentials are taken from ssh-agent or configured git credential helper.
+pub fn push_current_branch() -> Result<String> {
+    let repo = Repository::discover(".")?;
+    let head = repo.head()?;
+    let branch = match head.shorthand() {
+        Some(b) if head.is_branch() => b.to_string(),
+        _ => {
+            return Err(PolkahubError::Config(
+                "HEAD is detached, checkout a branch to push it".into(),
+            )
+            .into())
+        }
+    };
+    let config = repo.config()?;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            Cred::credential_helper(&config, url, username)
+        } else {
+            Cred::default()
+        }
+    });
+    let mut options = PushOptions::new();
+    options.remote_callbacks(callbacks);
+    let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+    repo.find_remote(REMOTE_NAME)?
+        .push(&[refspec.as_str()], Some(&mut options))?;
+    Ok(branch)
+}
@@ -0,0 +1,125 @@
+//! Tracing of HTTP traffic enabled with `-v`/`-vv` or `POLKAHUB_LOG`
+use anyhow::Result;
+use serde_json::Value;
+use std::{
+    env,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    sync::Arc,
+};
+use tracing::Level;
+
+/// json keys whose values never reach the log
+const SECRET_KEYS: &[&str] = &["password", "token", "secret", "code", "otpauth"];
+
+/// `-v` logs requests and response statuses, `-vv` adds redacted response bodies.
+/// With `POLKAHUB_LOG=<path>` the log is appended to the file instead of stderr.
+pub fn init(verbosity: u8) -> Result<()> {
+    let log_file = env::var("POLKAHUB_LOG").ok();
+    let level = match (verbosity, &log_file) {
+        (0, None) => return Ok(()),
+        (0, Some(_)) | (1, _) => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    // only our own events, not the ones of reqwest and hyper
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(format!("polkahub={}", level))
+        .with_target(false);
+    match log_file {
+        Some(path) => {
+            let file = Arc::new(OpenOptions::new().create(true).append(true).open(path)?);
+            builder
+                .with_ansi(false)
+                .with_writer(move || LogFile(file.clone()))
+                .init()
+        }
+        None => builder
+            .with_ansi(crate::parsing::stderr_colors_enabled())
+            .with_writer(io::stderr)
+            .init(),
+    }
+    Ok(())
+}
+
+struct LogFile(Arc<File>);
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self.0).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self.0).flush()
+    }
+}
+
+/// response body for the log, json is redacted and anything else is kept as is
+pub fn redact_text(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) => redact(&value).to_string(),
+        Err(_) => body.to_string(),
+    }
+}
+
+/// copy of a request or response body with passwords, tokens and codes masked
+pub fn redact(body: &Value) -> Value {
+    match body {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let secret = SECRET_KEYS.iter().any(|s| key.contains(s));
+                    let value = match value {
+                        Value::Null => Value::Null,
+                        _ if secret => Value::String("***".into()),
+                        _ => redact(value),
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(redact).collect()),
+        _ => body.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn secrets_are_masked() {
+        let body = json!({
+            "status": "ok",
+            "payload": {
+                "secret": "JBSWY3DPEHPK3PXP",
+                "otpauth_uri": "otpauth://totp/polkahub:alice?secret=JBSWY3DPEHPK3PXP",
+                "recovery_codes": ["1234", "5678"],
+                "expires_at": null,
+                "name": "ci",
+            },
+        });
+        assert_eq!(
+            redact(&body),
+            json!({
+                "status": "ok",
+                "payload": {
+                    "secret": "***",
+                    "otpauth_uri": "***",
+                    "recovery_codes": "***",
+                    "expires_at": null,
+                    "name": "ci",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn text_bodies_are_kept() {
+        assert_eq!(redact_text("Bad gateway"), "Bad gateway");
+        assert_eq!(
+            redact_text(r#"{"payload":{"token":"t0ken"}}"#),
+            r#"{"payload":{"token":"***"}}"#
+        );
+    }
+}
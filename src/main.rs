@@ -55,20 +55,67 @@
 //!
 use anyhow::Result;
 
+mod cache;
+mod completions;
+mod dev_server;
+mod error;
+mod git;
+mod logging;
 mod parsing;
-use parsing::{err, print_help, Action, Project};
+mod password;
+mod progress;
+mod rpc;
+#[cfg(test)]
+mod stub;
+use error::PolkahubError;
+use parsing::{err, init_output, print_help, Action, Project};
+use std::process;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let project = Project::new();
+    init_output(&project.color);
+    if let Err(e) = run(&project).await {
+        PolkahubError::from(e).print();
+        process::exit(1);
+    }
+}
+
+async fn run(project: &Project) -> Result<()> {
+    logging::init(if project.debug { 2 } else { project.verbose })?;
 
     match project.parse_action() {
         Action::Create => project.create().await,
         Action::Help => print_help(),
         Action::Find => project.find().await,
         Action::Install => project.install().await,
+        Action::Publish => project.publish().await,
+        Action::Status => project.status().await,
+        Action::List => project.list().await,
+        Action::Uninstall => project.uninstall().await,
+        Action::Upgrade => project.upgrade().await,
+        Action::Logs => project.logs().await,
+        Action::ProjectEdit => project.edit_project().await,
+        Action::ProjectDelete => project.delete_project().await,
+        Action::Yank => project.yank().await,
+        Action::Deprecate => project.deprecate().await,
+        Action::Info => project.info().await,
+        Action::OrgCreate => project.create_org().await,
+        Action::OrgAddMember => project.add_org_member().await,
+        Action::OrgRemoveMember => project.remove_org_member().await,
+        Action::TokenCreate => project.create_token().await,
+        Action::TokenList => project.list_tokens().await,
+        Action::TokenRevoke => project.revoke_token().await,
         Action::Register => project.register().await,
         Action::Login => project.login().await,
+        Action::AccountPassword => project.change_password().await,
+        Action::AccountResetPassword => project.reset_password().await,
+        Action::AccountVerify => project.verify().await,
+        Action::AccountTwoFactor => project.two_factor().await,
+        Action::CacheClear => project.clear_cache(),
+        Action::Completions => project.completions(),
+        Action::DevServer => project.dev_server().await,
+        Action::Complete => project.complete().await,
         Action::InputError(f) => err::<()>(f),
     }
 }
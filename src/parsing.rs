@@ -1,28 +1,48 @@
-use anyhow::{anyhow, Result};
-use circle_rs::{Infinite, Progress};
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
 use lazy_static;
+use qrcode::{render::unicode, QrCode};
 use regex::Regex;
-use reqwest::{self, header};
+use reqwest::{self, header, StatusCode};
 use rpassword;
+use semver::Version;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use structopt::StructOpt;
 use termion::{color, style};
-use tokio::{fs::File, io::AsyncReadExt};
+use tokio::{fs::File, io::AsyncReadExt, time::delay_for};
 use toml;
+use tracing::{debug, trace};
+
+use crate::{
+    cache, completions, dev_server,
+    error::PolkahubError,
+    git, logging, password,
+    progress::{self, Stage, Upload},
+    rpc,
+};
 
 lazy_static::lazy_static! {
     static ref PROJECT_FULL_NAME: Regex = Regex::new(r"^(?P<login>[\w\d-]+)/(?P<name>[a-z0-9-]+)@(?P<version>[\w\d.-]+)$")
         .unwrap_or_else(|_| panic!("invalid PROJECT_FULL_NAME pattern"));
+    static ref PROJECT_REF: Regex = Regex::new(r"^(?P<login>[\w\d-]+)/(?P<name>[a-z0-9-]+)(@(?P<version>[\w\d.-]+))?$")
+        .unwrap_or_else(|_| panic!("invalid PROJECT_REF pattern"));
+    // dot-atom local part and hostname domain of RFC 5322 addr-spec
+    static ref EMAIL: Regex = Regex::new(r"^[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+(\.[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+)*@([A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?\.)+[A-Za-z][A-Za-z0-9-]{1,62}$")
+        .unwrap_or_else(|_| panic!("invalid EMAIL pattern"));
     static ref PROJECT_NAME: Regex = Regex::new(r"^[a-z0-9-]+$").unwrap_or_else(|_| panic!("invalid PROJECT_NAME pattern"));
 }
 
 use std::{
+    cmp::Ordering,
     env,
     io::{self, Read, Write},
     path::{Path, PathBuf},
     str::FromStr,
     string::ToString,
+    sync::atomic::{AtomicBool, Ordering as AtomicOrdering},
+    time::{Duration, Instant},
 };
 
 pub const CREATE_URL: &str = "https://api-test.polkahub.org/api/v1/projects";
@@ -30,31 +50,159 @@ pub const INSTALL_URL: &str = "https://api-test.polkahub.org/api/v1/install";
 pub const FIND_URL: &str = "https://api-test.polkahub.org/api/v1/find";
 pub const REGISTER_URL: &str = "https://api-test.polkahub.org/api/v1/signup";
 pub const LOGIN_URL: &str = "https://api-test.polkahub.org/api/v1/login";
+pub const PUBLISH_URL: &str = "https://api-test.polkahub.org/api/v1/publish";
+pub const STATUS_URL: &str = "https://api-test.polkahub.org/api/v1/status";
+pub const LIST_URL: &str = "https://api-test.polkahub.org/api/v1/list";
+pub const UNINSTALL_URL: &str = "https://api-test.polkahub.org/api/v1/uninstall";
+pub const UPGRADE_URL: &str = "https://api-test.polkahub.org/api/v1/upgrade";
+pub const LOGS_URL: &str = "https://api-test.polkahub.org/api/v1/logs";
+pub const PROJECT_EDIT_URL: &str = "https://api-test.polkahub.org/api/v1/projects/edit";
+pub const PROJECT_DELETE_URL: &str = "https://api-test.polkahub.org/api/v1/projects/delete";
+pub const YANK_URL: &str = "https://api-test.polkahub.org/api/v1/yank";
+pub const DEPRECATE_URL: &str = "https://api-test.polkahub.org/api/v1/deprecate";
+pub const INFO_URL: &str = "https://api-test.polkahub.org/api/v1/info";
+pub const ORG_CREATE_URL: &str = "https://api-test.polkahub.org/api/v1/orgs";
+pub const ORG_ADD_MEMBER_URL: &str = "https://api-test.polkahub.org/api/v1/orgs/members/add";
+pub const ORG_REMOVE_MEMBER_URL: &str = "https://api-test.polkahub.org/api/v1/orgs/members/remove";
+pub const TOKEN_CREATE_URL: &str = "https://api-test.polkahub.org/api/v1/tokens";
+pub const TOKEN_LIST_URL: &str = "https://api-test.polkahub.org/api/v1/tokens/list";
+pub const TOKEN_REVOKE_URL: &str = "https://api-test.polkahub.org/api/v1/tokens/revoke";
+pub const PASSWORD_CHANGE_URL: &str = "https://api-test.polkahub.org/api/v1/account/password";
+pub const PASSWORD_RESET_REQUEST_URL: &str =
+    "https://api-test.polkahub.org/api/v1/account/reset-password";
+pub const PASSWORD_RESET_URL: &str =
+    "https://api-test.polkahub.org/api/v1/account/reset-password/confirm";
+pub const VERIFY_URL: &str = "https://api-test.polkahub.org/api/v1/account/verify";
+pub const LOGIN_2FA_URL: &str = "https://api-test.polkahub.org/api/v1/login/2fa";
+pub const TWO_FACTOR_ENABLE_URL: &str = "https://api-test.polkahub.org/api/v1/account/2fa/enable";
+pub const TWO_FACTOR_CONFIRM_URL: &str = "https://api-test.polkahub.org/api/v1/account/2fa/confirm";
+pub const TWO_FACTOR_DISABLE_URL: &str = "https://api-test.polkahub.org/api/v1/account/2fa/disable";
+pub const DEVICE_CODE_URL: &str = "https://api-test.polkahub.org/api/v1/login/device";
+pub const DEVICE_TOKEN_URL: &str = "https://api-test.polkahub.org/api/v1/login/device/token";
+pub const CHAINSPEC_UPLOAD_URL: &str = "https://api-test.polkahub.org/api/v1/chainspecs";
+/// prefix of all endpoints, replaced by `POLKAHUB_API_URL` when set
+pub const API_URL: &str = "https://api-test.polkahub.org/api/v1";
 pub const HELP_NOTION: &str = "Try running `polkahub help` to see all available options";
+/// first words of all actions, the shell scripts complete all but `__complete`
+pub const ACTIONS: &[&str] = &[
+    "create",
+    "find",
+    "help",
+    "install",
+    "publish",
+    "status",
+    "list",
+    "uninstall",
+    "destroy",
+    "upgrade",
+    "logs",
+    "project",
+    "yank",
+    "deprecate",
+    "info",
+    "org",
+    "token",
+    "register",
+    "auth",
+    "account",
+    "cache",
+    "completions",
+    "dev-server",
+    "__complete",
+];
 const MIN_PASSWORD_LENGTH: usize = 8;
 const MAX_PASSWORD_LENGTH: usize = 50;
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_EMAIL_LOCAL_LENGTH: usize = 64;
+const MAX_INPUT_ATTEMPTS: usize = 3;
+const STATUS_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+const NODE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+const MIN_COLUMN_WIDTH: usize = 20;
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+const COMPLETION_CACHE_TTL: Duration = Duration::from_secs(300);
+const COMPLETION_LIMIT: u32 = 100;
+const DEV_SERVER_ADDR: &str = "127.0.0.1:3030";
+
+static COLORS: AtomicBool = AtomicBool::new(true);
+/// stderr may be a terminal while stdout is piped and the other way around
+static STDERR_COLORS: AtomicBool = AtomicBool::new(true);
+
+/// `auto` keeps colors and spinners only on terminals and without a non-empty `NO_COLOR`
+pub fn init_output(color_choice: &str) {
+    let no_color = matches!(env::var("NO_COLOR"), Ok(v) if !v.is_empty());
+    let (colors, stderr_colors) = match color_choice {
+        "always" => (true, true),
+        "never" => (false, false),
+        _ => (
+            !no_color && termion::is_tty(&io::stdout()),
+            !no_color && termion::is_tty(&io::stderr()),
+        ),
+    };
+    COLORS.store(colors, AtomicOrdering::Relaxed);
+    STDERR_COLORS.store(stderr_colors, AtomicOrdering::Relaxed);
+    progress::set_enabled(stderr_colors);
+}
+
+pub fn colors_enabled() -> bool {
+    COLORS.load(AtomicOrdering::Relaxed)
+}
+
+pub fn stderr_colors_enabled() -> bool {
+    STDERR_COLORS.load(AtomicOrdering::Relaxed)
+}
+
+fn print_colored<C: color::Color>(c: C, s: &str) {
+    if colors_enabled() {
+        print!("{}{}{}", color::Fg(c), s, color::Fg(color::Reset))
+    } else {
+        print!("{}", s)
+    }
+}
 
 pub fn print_green(s: &str) {
-    let green = color::Fg(color::LightGreen);
-    print!("{}{}{}", green, s, color::Fg(color::Reset))
+    print_colored(color::LightGreen, s)
 }
 
 pub fn print_red(s: &str) {
-    print!("{}{}{}", color::Fg(color::Red), s, color::Fg(color::Reset))
+    print_colored(color::Red, s)
 }
 
 pub fn print_yellow(s: &str) {
-    let yellow = color::Fg(color::LightYellow);
-    print!("{}{}{}", yellow, s, color::Fg(color::Reset))
+    print_colored(color::LightYellow, s)
 }
 
 pub fn print_blue(s: &str) {
-    let blue = color::Fg(color::LightBlue);
-    print!("{}{}{}", blue, s, color::Fg(color::Reset))
+    print_colored(color::LightBlue, s)
 }
 
 pub fn print_italic(s: &str) {
-    print!("{}{}{}", style::Italic, s, style::Reset);
+    if colors_enabled() {
+        print!("{}{}{}", style::Italic, s, style::Reset);
+    } else {
+        print!("{}", s);
+    }
+}
+
+fn eprint_colored<C: color::Color>(c: C, s: &str) {
+    if stderr_colors_enabled() {
+        eprint!("{}{}{}", color::Fg(c), s, color::Fg(color::Reset))
+    } else {
+        eprint!("{}", s)
+    }
+}
+
+pub fn eprint_red(s: &str) {
+    eprint_colored(color::Red, s)
+}
+
+/// warnings go to stderr to keep piped output clean
+pub fn print_warning(s: &str) {
+    eprint_colored(color::LightYellow, "WARN: ");
+    if stderr_colors_enabled() {
+        eprintln!("{}{}{}", style::Italic, s, style::Reset);
+    } else {
+        eprintln!("{}", s);
+    }
 }
 
 /// Main hub config
@@ -79,7 +227,7 @@ struct Chainspec {
     path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 struct Node {
     telemetry_url: String,
     listen_addr: String,
@@ -109,6 +257,9 @@ pub struct Project {
     /// project name
     ///
     pub name: Option<String>,
+    /// additional arguments, e.g. upgrade <app_name> <login/name@version>
+    ///
+    pub args: Vec<String>,
     ///alias your deployed version in your environment
     ///
     #[structopt(short = "a")]
@@ -117,6 +268,146 @@ pub struct Project {
     ///
     #[structopt(short = "h")]
     pub hub_file: Option<String>,
+    ///tag local git repository with published version
+    ///
+    #[structopt(long = "tag")]
+    pub tag: bool,
+    ///add created repository as `polkahub` remote of your git repository
+    ///
+    #[structopt(long = "git")]
+    pub git: bool,
+    ///push current branch to `polkahub` remote after create
+    ///
+    #[structopt(long = "push")]
+    pub push: bool,
+    ///keep refreshing status until deployment is running or failed
+    ///
+    #[structopt(long = "watch")]
+    pub watch: bool,
+    ///wait until installed node is healthy
+    ///
+    #[structopt(long = "wait")]
+    pub wait: bool,
+    ///seconds to wait for installed node
+    ///
+    #[structopt(long = "timeout", default_value = "300")]
+    pub timeout: u64,
+    ///print result as json
+    ///
+    #[structopt(long = "json")]
+    pub json: bool,
+    ///show only entries of this project
+    ///
+    #[structopt(long = "project")]
+    pub project: Option<String>,
+    ///sort entries by name, project, version or date
+    ///
+    #[structopt(long = "sort", default_value = "name", possible_values = &["name", "project", "version", "date"])]
+    pub sort: String,
+    ///do not ask for confirmation
+    ///
+    #[structopt(long = "yes", short = "y")]
+    pub yes: bool,
+    ///only show what would be done
+    ///
+    #[structopt(long = "dry-run")]
+    pub dry_run: bool,
+    ///upgrade app back to its previous version
+    ///
+    #[structopt(long = "rollback")]
+    pub rollback: bool,
+    ///keep streaming new log lines
+    ///
+    #[structopt(long = "follow", short = "f")]
+    pub follow: bool,
+    ///show logs newer than timestamp or relative duration, e.g. 10m
+    ///
+    #[structopt(long = "since")]
+    pub since: Option<String>,
+    ///number of last log lines to show
+    ///
+    #[structopt(long = "tail")]
+    pub tail: Option<u64>,
+    ///show only log lines matching the pattern
+    ///
+    #[structopt(long = "grep")]
+    pub grep: Option<String>,
+    ///new project description
+    ///
+    #[structopt(long = "description")]
+    pub description: Option<String>,
+    ///new project homepage
+    ///
+    #[structopt(long = "homepage")]
+    pub homepage: Option<String>,
+    ///comma separated project tags
+    ///
+    #[structopt(long = "tags", use_delimiter = true)]
+    pub tags: Option<Vec<String>>,
+    ///project visibility
+    ///
+    #[structopt(long = "visibility", possible_values = &["public", "private"])]
+    pub visibility: Option<String>,
+    ///deprecation message
+    ///
+    #[structopt(long = "message", short = "m")]
+    pub message: Option<String>,
+    ///install version even if it was yanked
+    ///
+    #[structopt(long = "allow-yanked")]
+    pub allow_yanked: bool,
+    ///look for projects of this user only
+    ///
+    #[structopt(long = "user")]
+    pub user: Option<String>,
+    ///number of results per page
+    ///
+    #[structopt(long = "limit", default_value = "20")]
+    pub limit: u32,
+    ///page of results to show
+    ///
+    #[structopt(long = "page", default_value = "1")]
+    pub page: u32,
+    ///create project under organization namespace
+    ///
+    #[structopt(long = "org")]
+    pub org: Option<String>,
+    ///role of organization member
+    ///
+    #[structopt(long = "role", possible_values = &["owner", "publisher", "deployer"])]
+    pub role: Option<String>,
+    ///name of api token
+    ///
+    #[structopt(long = "name")]
+    pub token_name: Option<String>,
+    ///comma separated actions api token is allowed to do
+    ///
+    #[structopt(long = "scope", use_delimiter = true)]
+    pub scope: Option<Vec<String>>,
+    ///api token lifetime, e.g. 12h, 30d, 4w
+    ///
+    #[structopt(long = "expires")]
+    pub expires: Option<String>,
+    ///log in by approving a code in the browser
+    ///
+    #[structopt(long = "device")]
+    pub device: bool,
+    ///log http requests, -vv also logs response bodies
+    ///
+    #[structopt(long = "verbose", short = "v", parse(from_occurrences))]
+    pub verbose: u8,
+    ///same as -vv
+    ///
+    #[structopt(long = "debug")]
+    pub debug: bool,
+    ///colored output and spinners: auto, always or never
+    ///
+    #[structopt(long = "color", default_value = "auto", possible_values = &["auto", "always", "never"])]
+    pub color: String,
+    ///answer find and info from the local cache
+    ///
+    #[structopt(long = "offline")]
+    pub offline: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -133,6 +424,109 @@ pub struct InstalledPayload {
     pub ws_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PublishedPayload {
+    pub login: String,
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusPayload {
+    pub app_name: String,
+    /// owner of the installed project
+    pub login: String,
+    pub project_name: String,
+    pub state: DeploymentState,
+    pub version: String,
+    pub node_version: Option<String>,
+    /// seconds since node start
+    pub uptime: Option<u64>,
+    pub http_url: String,
+    pub ws_url: String,
+    /// version installed before the last upgrade
+    pub previous_version: Option<String>,
+    node: Option<Node>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpgradedPayload {
+    pub app_name: String,
+    pub version: String,
+    pub http_url: String,
+    pub ws_url: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeploymentState {
+    Pending,
+    Running,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListPayload {
+    pub projects: Vec<ListedProject>,
+    pub apps: Vec<ListedApp>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListedProject {
+    pub login: String,
+    pub name: String,
+    pub versions: Vec<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListedApp {
+    pub app_name: String,
+    pub project_name: String,
+    pub version: String,
+    pub http_url: String,
+    pub ws_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub login: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub repo_url: String,
+    pub versions: Vec<VersionInfo>,
+    pub downloads: u64,
+    pub deploys: u64,
+    pub deployments: Vec<ListedApp>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub published_at: Option<String>,
+    pub chainspec_hash: Option<String>,
+    #[serde(default)]
+    pub yanked: bool,
+    pub deprecated: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatedTokenPayload {
+    pub name: String,
+    pub token: String,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenInfo {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Failure {
     pub status: String,
@@ -163,6 +557,12 @@ struct FoundProject {
     name: String,
     version: String,
     description: Option<String>,
+    #[serde(default)]
+    yanked: bool,
+    /// deprecation message
+    deprecated: Option<String>,
+    /// RFC 3339 publish timestamp
+    published_at: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -174,6 +574,79 @@ enum InstalledResponse {
     ErrResult { reason: String },
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum PublishedResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: PublishedPayload },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum StatusResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: Box<StatusPayload> },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum ListResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: ListPayload },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+/// response of actions without payload
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum AckResponse {
+    #[serde(rename = "ok")]
+    OkResult,
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum UpgradedResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: UpgradedPayload },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum InfoResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: ProjectInfo },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum TokenCreatedResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: CreatedTokenPayload },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum TokenListResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: Vec<TokenInfo> },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(tag = "status")]
 enum RegisteredResponse {
@@ -188,6 +661,9 @@ enum RegisteredResponse {
 enum LoginedResponse {
     #[serde(rename = "ok")]
     OkResult { payload: LoginedResponsePayload },
+    /// password is correct, TOTP code is expected for the session
+    #[serde(rename = "2fa_required")]
+    TwoFactorRequired { payload: TwoFactorSession },
     #[serde(rename = "error")]
     ErrResult { reason: String },
 }
@@ -197,15 +673,120 @@ struct LoginedResponsePayload {
     token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum ChainspecUploadedResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: UploadedChainspec },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+struct UploadedChainspec {
+    hash: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum DeviceCodeResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: DeviceCode },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+struct DeviceCode {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    /// seconds between token requests
+    #[serde(default = "default_device_interval")]
+    interval: u64,
+    /// seconds the code stays valid
+    #[serde(default = "default_device_expires_in")]
+    expires_in: u64,
+}
+
+fn default_device_interval() -> u64 {
+    5
+}
+
+fn default_device_expires_in() -> u64 {
+    900
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum DeviceTokenResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: LoginedResponsePayload },
+    /// code is not approved yet
+    #[serde(rename = "pending")]
+    Pending,
+    /// polling too often, interval must be increased
+    #[serde(rename = "slow_down")]
+    SlowDown,
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Deserialize, Debug)]
+struct TwoFactorSession {
+    session: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status")]
+enum TwoFactorEnabledResponse {
+    #[serde(rename = "ok")]
+    OkResult { payload: TwoFactorEnrollment },
+    #[serde(rename = "error")]
+    ErrResult { reason: String },
+}
+
+#[derive(Debug)]
 pub enum Action {
     Install,
     Create,
     Find,
+    Publish,
+    Status,
+    List,
+    Uninstall,
+    Upgrade,
+    Logs,
+    ProjectEdit,
+    ProjectDelete,
+    Yank,
+    Deprecate,
+    Info,
+    OrgCreate,
+    OrgAddMember,
+    OrgRemoveMember,
+    TokenCreate,
+    TokenList,
+    TokenRevoke,
     Register,
     Login,
+    AccountPassword,
+    AccountResetPassword,
+    AccountVerify,
+    AccountTwoFactor,
+    CacheClear,
+    Completions,
+    DevServer,
+    /// hidden, prints project references for shell completion
+    Complete,
     Help,
-    InputError(Failure),
+    InputError(PolkahubError),
 }
 
 impl FromStr for Action {
@@ -217,12 +798,37 @@ impl FromStr for Action {
             "find" => Ok(Action::Find),
             "help" => Ok(Action::Help),
             "install" => Ok(Action::Install),
+            "publish" => Ok(Action::Publish),
+            "status" => Ok(Action::Status),
+            "list" => Ok(Action::List),
+            "uninstall" | "destroy" => Ok(Action::Uninstall),
+            "upgrade" => Ok(Action::Upgrade),
+            "logs" => Ok(Action::Logs),
+            "project edit" => Ok(Action::ProjectEdit),
+            "project delete" => Ok(Action::ProjectDelete),
+            "yank" => Ok(Action::Yank),
+            "deprecate" => Ok(Action::Deprecate),
+            "info" => Ok(Action::Info),
+            "org create" => Ok(Action::OrgCreate),
+            "org add-member" => Ok(Action::OrgAddMember),
+            "org remove-member" => Ok(Action::OrgRemoveMember),
+            "token create" => Ok(Action::TokenCreate),
+            "token list" => Ok(Action::TokenList),
+            "token revoke" => Ok(Action::TokenRevoke),
             "register" => Ok(Action::Register),
             "auth" => Ok(Action::Login),
-            _ => Ok(Action::InputError(Failure {
-                status: "input error".to_owned(),
-                reason: format!("{} - is invalid action. {}", s, HELP_NOTION),
-            })),
+            "account password" => Ok(Action::AccountPassword),
+            "account reset-password" => Ok(Action::AccountResetPassword),
+            "account verify" => Ok(Action::AccountVerify),
+            "account 2fa" => Ok(Action::AccountTwoFactor),
+            "cache clear" => Ok(Action::CacheClear),
+            "completions" => Ok(Action::Completions),
+            "dev-server" => Ok(Action::DevServer),
+            "__complete" => Ok(Action::Complete),
+            _ => Ok(Action::InputError(PolkahubError::Input(format!(
+                "{} - is invalid action.",
+                s
+            )))),
         }
     }
 }
@@ -238,7 +844,7 @@ impl Default for Hub {
 }
 
 impl CreatedResponse {
-    pub fn handle(&self) {
+    pub fn handle(&self) -> Result<()> {
         match &self {
             CreatedResponse::OkResult { payload } => {
                 print_green("done\n");
@@ -248,19 +854,17 @@ impl CreatedResponse {
                 println!(" -> {}", payload.ws_url);
                 print_italic("remote");
                 println!(" -> {}", payload.repo_url);
+                Ok(())
             }
             CreatedResponse::ErrResult { reason } => {
-                let _ = err::<()>(Failure {
-                    status: "Could not create project.\n".into(),
-                    reason: format!("Reason: {}", reason),
-                });
+                err(PolkahubError::server("Could not create project", reason))
             }
         }
     }
 }
 
 impl InstalledResponse {
-    pub fn handle(&self) {
+    pub fn handle(&self) -> Result<()> {
         match &self {
             InstalledResponse::OkResult { payload } => {
                 print_green("done\n");
@@ -268,162 +872,1263 @@ impl InstalledResponse {
                 println!(" -> {}", payload.http_url);
                 print_blue("ws    ");
                 println!(" -> {}", payload.ws_url);
+                Ok(())
             }
             InstalledResponse::ErrResult { reason } => {
-                let _ = err::<()>(Failure {
-                    status: "Could not create project.\n".into(),
-                    reason: format!("Reason: {}", reason),
-                });
+                err(PolkahubError::server("Could not install project", reason))
             }
         }
     }
 }
 
 impl FoundResponse {
-    pub fn handle(&self) {
+    pub fn handle(&self) -> Result<()> {
         match self {
             FoundResponse::OkResult { payload } => {
                 if payload.is_empty() {
                     print_green("Looks like no versions deployed yet!\n");
                     print!("");
                 } else {
-                    payload.iter().for_each(|p| {
-                        println!(
-                            "{}/{}@{}\t{}",
-                            p.login,
-                            p.name,
-                            p.version,
-                            p.description.as_ref().unwrap_or(&"".to_string())
-                        );
-                    })
+                    print_table(
+                        &["VERSION", "PUBLISHED", "STATUS", "DESCRIPTION"],
+                        payload
+                            .iter()
+                            .map(|p| {
+                                vec![
+                                    format!("{}/{}@{}", p.login, p.name, p.version),
+                                    format_date(p.published_at.as_ref()),
+                                    version_status(p.yanked, p.deprecated.as_ref()),
+                                    p.description.clone().unwrap_or_default(),
+                                ]
+                            })
+                            .collect(),
+                    );
                 }
+                Ok(())
             }
             FoundResponse::ErrResult { reason } => {
-                let _ = err::<()>(Failure {
-                    status: "Could not find project.\n".into(),
-                    reason: format!("Reason: {}", reason),
-                });
+                err(PolkahubError::server("Could not find project", reason))
             }
         }
     }
-}
 
-impl RegisteredResponse {
-    pub fn handle(&self) {
-        match &self {
-            RegisteredResponse::OkResult => {
-                print_green("done\n");
-            }
-            RegisteredResponse::ErrResult { reason } => {
-                let _ = err::<()>(Failure {
-                    status: "Could not register new user.\n".into(),
-                    reason: format!("Reason: {}", reason),
-                });
+    /// order versions by `sort` key: name, version (newest first) or date (newest first)
+    fn sort(&mut self, sort: &str) {
+        if let FoundResponse::OkResult { payload } = self {
+            match sort {
+                "version" => payload.sort_by(|a, b| compare_versions(&b.version, &a.version)),
+                "date" => payload.sort_by(|a, b| b.published_at.cmp(&a.published_at)),
+                _ => payload.sort_by(|a, b| {
+                    (&a.login, &a.name)
+                        .cmp(&(&b.login, &b.name))
+                        .then_with(|| compare_versions(&a.version, &b.version))
+                }),
             }
         }
     }
 }
 
-impl LoginedResponse {
-    pub fn handle(&self) {
+impl PublishedResponse {
+    pub fn handle(&self) -> Result<()> {
         match &self {
-            LoginedResponse::OkResult {
-                payload: LoginedResponsePayload { token },
-            } => match store_token(token) {
-                Ok(()) => print_green("done\n"),
-                Err(reason) => {
-                    let _ = err::<()>(Failure {
-                        status: "Could not login.\n".into(),
-                        reason: format!("Reason: {}", reason),
-                    });
-                }
-            },
-            LoginedResponse::ErrResult { reason } => {
-                let _ = err::<()>(Failure {
-                    status: "Could not login.\n".into(),
-                    reason: format!("Reason: {}", reason),
-                });
+            PublishedResponse::OkResult { payload } => {
+                print_green("done\n");
+                print_blue("version");
+                println!(" -> {}/{}@{}", payload.login, payload.name, payload.version);
+                Ok(())
+            }
+            PublishedResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not publish version", reason))
             }
         }
     }
 }
 
-impl Project {
-    pub fn new() -> Project {
-        Project::from_args()
+impl DeploymentState {
+    /// deployment will not change its state without user actions
+    pub fn is_terminal(self) -> bool {
+        self != DeploymentState::Pending
     }
 
-    pub async fn create(&self) -> Result<()> {
-        self.send_create_request(CREATE_URL).await?.handle();
-        Ok(())
+    fn print(self) {
+        match self {
+            DeploymentState::Pending => print_yellow("pending"),
+            DeploymentState::Running => print_green("running"),
+            DeploymentState::Failed => print_red("failed"),
+        }
     }
+}
 
-    pub async fn find(&self) -> Result<()> {
-        self.send_find_request(FIND_URL).await?.handle();
-        Ok(())
+impl StatusResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            StatusResponse::OkResult { payload } => {
+                print_blue("app   ");
+                println!(" -> {}@{}", payload.app_name, payload.version);
+                print_blue("state ");
+                print!(" -> ");
+                payload.state.print();
+                println!();
+                if let Some(node_version) = &payload.node_version {
+                    print_blue("node  ");
+                    println!(" -> {}", node_version);
+                }
+                if let Some(uptime) = payload.uptime {
+                    print_blue("uptime");
+                    println!(" -> {}", format_duration(uptime));
+                }
+                print_blue("https ");
+                println!(" -> {}", payload.http_url);
+                print_blue("ws    ");
+                println!(" -> {}", payload.ws_url);
+                Ok(())
+            }
+            StatusResponse::ErrResult { reason } => err(PolkahubError::server(
+                "Could not get deployment status",
+                reason,
+            )),
+        }
     }
+}
 
-    pub async fn install(&self) -> Result<()> {
-        self.send_install_request(INSTALL_URL).await?.handle();
-        Ok(())
+impl ListPayload {
+    /// keep only entries of `project` and order them by `sort` key
+    fn filter_and_sort(&mut self, project: Option<&str>, sort: &str) {
+        if let Some(project) = project {
+            self.projects.retain(|p| p.name == project);
+            self.apps.retain(|a| a.project_name == project);
+        }
+        match sort {
+            "version" => {
+                let latest = |p: &ListedProject| {
+                    p.versions
+                        .iter()
+                        .max_by(|a, b| compare_versions(a, b))
+                        .cloned()
+                        .unwrap_or_default()
+                };
+                self.projects
+                    .sort_by(|a, b| compare_versions(&latest(a), &latest(b)));
+                self.apps
+                    .sort_by(|a, b| compare_versions(&a.version, &b.version));
+            }
+            "project" => {
+                self.projects.sort_by(|a, b| a.name.cmp(&b.name));
+                self.apps.sort_by(|a, b| {
+                    (&a.project_name, &a.app_name).cmp(&(&b.project_name, &b.app_name))
+                });
+            }
+            _ => {
+                self.projects.sort_by(|a, b| a.name.cmp(&b.name));
+                self.apps.sort_by(|a, b| a.app_name.cmp(&b.app_name));
+            }
+        }
     }
+}
 
-    pub async fn register(&self) -> Result<()> {
+impl ListResponse {
+    pub fn handle(&self, json: bool) -> Result<()> {
+        match &self {
+            ListResponse::OkResult { payload } if json => {
+                println!("{}", serde_json::to_string_pretty(payload)?);
+                Ok(())
+            }
+            ListResponse::OkResult { payload } => {
+                print_green("Projects\n");
+                if payload.projects.is_empty() {
+                    println!("Looks like no projects created yet!");
+                } else {
+                    print_table(
+                        &["PROJECT", "VERSIONS", "DESCRIPTION"],
+                        payload
+                            .projects
+                            .iter()
+                            .map(|p| {
+                                vec![
+                                    format!("{}/{}", p.login, p.name),
+                                    p.versions.join(", "),
+                                    p.description.clone().unwrap_or_default(),
+                                ]
+                            })
+                            .collect(),
+                    );
+                }
+                print_green("\nApps\n");
+                if payload.apps.is_empty() {
+                    println!("Looks like no apps installed yet!");
+                } else {
+                    print_table(
+                        &["APP", "PROJECT", "VERSION", "HTTPS", "WS"],
+                        payload
+                            .apps
+                            .iter()
+                            .map(|a| {
+                                vec![
+                                    a.app_name.clone(),
+                                    a.project_name.clone(),
+                                    a.version.clone(),
+                                    a.http_url.clone(),
+                                    a.ws_url.clone(),
+                                ]
+                            })
+                            .collect(),
+                    );
+                }
+                Ok(())
+            }
+            ListResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not list projects", reason))
+            }
+        }
+    }
+}
+
+impl AckResponse {
+    /// print `done_msg` on success, fail with `context` of the action otherwise
+    pub fn handle(&self, context: &str, done_msg: &str) -> Result<()> {
+        match &self {
+            AckResponse::OkResult => {
+                print_green(done_msg);
+                Ok(())
+            }
+            AckResponse::ErrResult { reason } => err(PolkahubError::server(context, reason)),
+        }
+    }
+}
+
+impl UpgradedResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            UpgradedResponse::OkResult { payload } => {
+                print_green("done\n");
+                print_blue("app   ");
+                println!(" -> {}@{}", payload.app_name, payload.version);
+                print_blue("https ");
+                println!(" -> {}", payload.http_url);
+                print_blue("ws    ");
+                println!(" -> {}", payload.ws_url);
+                Ok(())
+            }
+            UpgradedResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not upgrade app", reason))
+            }
+        }
+    }
+}
+
+impl InfoResponse {
+    pub fn handle(&self, json: bool) -> Result<()> {
+        match &self {
+            InfoResponse::OkResult { payload } if json => {
+                println!("{}", serde_json::to_string_pretty(payload)?);
+                Ok(())
+            }
+            InfoResponse::OkResult { payload } => {
+                print_blue("owner      ");
+                println!(" -> {}", payload.login);
+                print_blue("project    ");
+                println!(" -> {}", payload.name);
+                if let Some(description) = &payload.description {
+                    print_blue("description");
+                    println!(" -> {}", description);
+                }
+                print_blue("created    ");
+                println!(" -> {}", format_date(payload.created_at.as_ref()));
+                print_blue("repository ");
+                println!(" -> {}", payload.repo_url);
+                print_blue("downloads  ");
+                println!(" -> {}", payload.downloads);
+                print_blue("deploys    ");
+                println!(" -> {}", payload.deploys);
+
+                print_green("\nVersions\n");
+                print_table(
+                    &["VERSION", "PUBLISHED", "STATUS", "CHAINSPEC"],
+                    payload
+                        .versions
+                        .iter()
+                        .map(|v| {
+                            vec![
+                                v.version.clone(),
+                                format_date(v.published_at.as_ref()),
+                                version_status(v.yanked, v.deprecated.as_ref()),
+                                v.chainspec_hash.clone().unwrap_or_default(),
+                            ]
+                        })
+                        .collect(),
+                );
+                print_green("\nActive deployments\n");
+                if payload.deployments.is_empty() {
+                    println!("Looks like no apps installed yet!");
+                } else {
+                    print_table(
+                        &["APP", "VERSION", "HTTPS", "WS"],
+                        payload
+                            .deployments
+                            .iter()
+                            .map(|a| {
+                                vec![
+                                    a.app_name.clone(),
+                                    a.version.clone(),
+                                    a.http_url.clone(),
+                                    a.ws_url.clone(),
+                                ]
+                            })
+                            .collect(),
+                    );
+                }
+                Ok(())
+            }
+            InfoResponse::ErrResult { reason } => err(PolkahubError::NotFound {
+                context: "Could not get project info".into(),
+                reason: reason.clone(),
+            }),
+        }
+    }
+}
+
+impl TokenCreatedResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            TokenCreatedResponse::OkResult { payload } => {
+                print_green("done\n");
+                print_blue("name   ");
+                println!(" -> {}", payload.name);
+                print_blue("token  ");
+                println!(" -> {}", payload.token);
+                if let Some(expires_at) = &payload.expires_at {
+                    print_blue("expires");
+                    println!(" -> {}", expires_at);
+                }
+                print_warning(
+                    "Save the token now, it will not be shown again. Use it as POLKAHUB_TOKEN.",
+                );
+                Ok(())
+            }
+            TokenCreatedResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not create token", reason))
+            }
+        }
+    }
+}
+
+impl TokenListResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            TokenListResponse::OkResult { payload } => {
+                if payload.is_empty() {
+                    print_green("Looks like no tokens created yet!\n");
+                } else {
+                    print_table(
+                        &["NAME", "SCOPE", "CREATED", "EXPIRES", "LAST USED"],
+                        payload
+                            .iter()
+                            .map(|t| {
+                                vec![
+                                    t.name.clone(),
+                                    t.scopes.join(","),
+                                    format_date(t.created_at.as_ref()),
+                                    format_date(t.expires_at.as_ref()),
+                                    format_date(t.last_used_at.as_ref()),
+                                ]
+                            })
+                            .collect(),
+                    );
+                }
+                Ok(())
+            }
+            TokenListResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not list tokens", reason))
+            }
+        }
+    }
+}
+
+impl RegisteredResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            RegisteredResponse::OkResult => {
+                print_green("done\n");
+                print_italic("Check your email and run `polkahub account verify <code>`\n");
+                Ok(())
+            }
+            RegisteredResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not register new user", reason))
+            }
+        }
+    }
+}
+
+impl LoginedResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            LoginedResponse::OkResult {
+                payload: LoginedResponsePayload { token },
+            } => {
+                store_token(token)?;
+                print_green("done\n");
+                Ok(())
+            }
+            LoginedResponse::TwoFactorRequired { .. } => err(PolkahubError::Auth(
+                "Could not login: authentication code is required".into(),
+            )),
+            LoginedResponse::ErrResult { reason } => {
+                err(PolkahubError::Auth(format!("Could not login: {}", reason)))
+            }
+        }
+    }
+}
+
+impl TwoFactorEnabledResponse {
+    pub fn handle(&self) -> Result<()> {
+        match &self {
+            TwoFactorEnabledResponse::OkResult { payload } => {
+                println!("Scan the code with your authenticator app:\n");
+                let qr = QrCode::new(payload.otpauth_uri.as_bytes())?
+                    .render::<unicode::Dense1x2>()
+                    .dark_color(unicode::Dense1x2::Light)
+                    .light_color(unicode::Dense1x2::Dark)
+                    .build();
+                println!("{}\n", qr);
+                print_blue("uri   ");
+                println!(" -> {}", payload.otpauth_uri);
+                print_blue("secret");
+                println!(" -> {}", payload.secret);
+                Ok(())
+            }
+            TwoFactorEnabledResponse::ErrResult { reason } => err(PolkahubError::server(
+                "Could not enable two-factor authentication",
+                reason,
+            )),
+        }
+    }
+}
+
+impl Project {
+    pub fn new() -> Project {
+        Project::from_args()
+    }
+
+    pub async fn create(&self) -> Result<()> {
+        if self.push && !self.git {
+            return err(PolkahubError::Input(
+                "--push requires --git to add the `polkahub` remote first.".into(),
+            ));
+        }
+        let response = self.send_create_request(CREATE_URL).await?;
+        response.handle()?;
+        if let CreatedResponse::OkResult { payload } = &response {
+            if self.git {
+                self.add_git_remote(&payload.repo_url)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn find(&self) -> Result<()> {
+        let name = self.name.clone().unwrap_or_else(|| "".to_string());
+        if self.user.is_none() {
+            check_zero_len(
+                &name,
+                "You must provide a project name or --user to look for.".into(),
+            )?;
+        }
+        let mut response = self.send_search_request(FIND_URL, &name).await?;
+        response.sort(&self.sort);
+        response.handle()?;
+        if let FoundResponse::OkResult { payload } = &response {
+            if payload.len() as u32 >= self.limit {
+                print_italic(&format!(
+                    "\nShowing page {}, try --page {} for more\n",
+                    self.page,
+                    self.page + 1
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn publish(&self) -> Result<()> {
+        let response = self.send_publish_request(PUBLISH_URL).await?;
+        response.handle()?;
+        if let PublishedResponse::OkResult { payload } = &response {
+            if self.tag {
+                let tag = git::tag_version(&payload.version)?;
+                print_italic("tag");
+                println!("     -> {}", tag);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn install(&self) -> Result<()> {
+        let response = self.send_install_request(INSTALL_URL).await?;
+        response.handle()?;
+        if let InstalledResponse::OkResult { payload } = &response {
+            if self.wait {
+                self.wait_for_node(&payload.http_url).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn status(&self) -> Result<()> {
+        let name = self.name.clone().unwrap_or_else(|| "".to_string());
+        check_zero_len(&name, "You must provide an app name to check.".into())?;
+        check_name("App", &name)?;
+        println!("\nChecking {} deployment", name);
+
+        let mut last_state = None;
+        loop {
+            let response = self.send_status_request(STATUS_URL, &name).await?;
+            let state = match &response {
+                StatusResponse::OkResult { payload } => payload.state,
+                StatusResponse::ErrResult { .. } => return response.handle(),
+            };
+            // in watch mode print only state changes
+            if last_state != Some(state) {
+                response.handle()?;
+            }
+            if !self.watch || state.is_terminal() {
+                return Ok(());
+            }
+            last_state = Some(state);
+            delay_for(STATUS_WATCH_INTERVAL).await;
+        }
+    }
+
+    pub async fn list(&self) -> Result<()> {
+        let mut response = self.send_list_request(LIST_URL).await?;
+        if let ListResponse::OkResult { payload } = &mut response {
+            payload.filter_and_sort(self.project.as_deref(), &self.sort);
+        }
+        response.handle(self.json)
+    }
+
+    pub async fn uninstall(&self) -> Result<()> {
+        let name = self.name.clone().unwrap_or_else(|| "".to_string());
+        check_zero_len(&name, "You must provide an app name to uninstall.".into())?;
+        check_name("App", &name)?;
+        println!("\nChecking {} deployment", name);
+
+        let app = match self.send_status_request(STATUS_URL, &name).await? {
+            StatusResponse::OkResult { payload } => payload,
+            StatusResponse::ErrResult { reason } => {
+                return err(PolkahubError::AppNotFound {
+                    context: "Could not find app".into(),
+                    reason,
+                })
+            }
+        };
+        print_yellow("Will remove:\n");
+        print_blue("app   ");
+        println!(" -> {}@{}", app.app_name, app.version);
+        print_blue("https ");
+        println!(" -> {}", app.http_url);
+        print_blue("ws    ");
+        println!(" -> {}", app.ws_url);
+        if self.dry_run {
+            return Ok(());
+        }
+        if !self.yes && !confirm(&format!("Uninstall {}?", name))? {
+            println!("Aborted");
+            return Ok(());
+        }
+
+        self.send_uninstall_request(UNINSTALL_URL, &name)
+            .await?
+            .handle("Could not uninstall app", "done\n")
+    }
+
+    pub async fn upgrade(&self) -> Result<()> {
+        let name = self.name.clone().unwrap_or_else(|| "".to_string());
+        check_zero_len(&name, "You must provide an app name to upgrade.".into())?;
+        check_name("App", &name)?;
+        let target = if self.rollback {
+            None
+        } else {
+            let full_name = self.args.first().cloned().unwrap_or_default();
+            let project_metadata = parse_full_name_project(&full_name)?;
+            self.check_not_yanked(&project_metadata).await?;
+            Some(project_metadata)
+        };
+        println!("\nChecking {} deployment", name);
+
+        let app = match self.send_status_request(STATUS_URL, &name).await? {
+            StatusResponse::OkResult { payload } => payload,
+            StatusResponse::ErrResult { reason } => {
+                return err(PolkahubError::AppNotFound {
+                    context: "Could not find app".into(),
+                    reason,
+                })
+            }
+        };
+        let installed_project = format!("{}/{}", app.login, app.project_name);
+        let (project, version, node) = match &target {
+            Some(project_metadata) => (
+                format!("{}/{}", project_metadata.login, project_metadata.name),
+                project_metadata.version.clone(),
+                self.read_hub().await?.node.or_else(|| app.node.clone()),
+            ),
+            None => match app.previous_version.clone() {
+                Some(v) => (installed_project.clone(), v, app.node.clone()),
+                None => {
+                    return err(PolkahubError::Input(format!(
+                        "{} has no previous version to roll back to.",
+                        name
+                    )))
+                }
+            },
+        };
+
+        let mut changed = print_change("project      ", &installed_project, &project);
+        changed |= print_change("version      ", &app.version, &version);
+        if let (Some(old), Some(new)) = (&app.node, &node) {
+            changed |= print_change("telemetry_url", &old.telemetry_url, &new.telemetry_url);
+            changed |= print_change("listen_addr  ", &old.listen_addr, &new.listen_addr);
+        }
+        if !changed {
+            print_green("Already up to date\n");
+            return Ok(());
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+        if !self.yes && !confirm(&format!("Upgrade {}?", name))? {
+            println!("Aborted");
+            return Ok(());
+        }
+
+        let body = match target {
+            Some(project_metadata) => json!({
+                "app_name": name,
+                "login": project_metadata.login,
+                "project_name": project_metadata.name,
+                "version": project_metadata.version,
+                "node": node,
+            }),
+            None => json!({
+                "app_name": name,
+                "rollback": true,
+            }),
+        };
+        self.send_upgrade_request(UPGRADE_URL, &name, body)
+            .await?
+            .handle()
+    }
+
+    pub async fn logs(&self) -> Result<()> {
+        let name = self.name.clone().unwrap_or_else(|| "".to_string());
+        check_zero_len(&name, "You must provide an app name to show logs.".into())?;
+        check_name("App", &name)?;
+        let grep = match &self.grep {
+            Some(pattern) => match Regex::new(pattern) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    return err(PolkahubError::Input(format!(
+                        "Invalid --grep pattern: {}",
+                        e
+                    )))
+                }
+            },
+            None => None,
+        };
+        let body = json!({
+            "app_name": name,
+            "follow": self.follow,
+            "since": self.since,
+            "tail": self.tail,
+        });
+
+        let mut response = self
+            .authorized_client()?
+            .post(&api_url(LOGS_URL))
+            .json(&body)
+            .send()
+            .await?;
+        let is_json = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| v.starts_with("application/json"))
+            .is_some();
+        let status = response.status();
+        if is_json || !status.is_success() {
+            let reason = match serde_json::from_str::<Failure>(&response.text().await?) {
+                Ok(f) => f.reason,
+                Err(_) => format!("server responded with {}", status),
+            };
+            return err(PolkahubError::server("Could not get logs", reason));
+        }
+
+        stream_log_lines(&mut response, grep.as_ref(), print_log_line).await
+    }
+
+    pub async fn edit_project(&self) -> Result<()> {
+        let name = self.args.first().cloned().unwrap_or_default();
+        check_zero_len(&name, "You must provide name of a project to edit.".into())?;
+        check_name("Project", &name)?;
+
+        let mut body = json!({
+            "project_name": name,
+        });
+        let changes = [
+            ("description", self.description.as_ref().map(|d| json!(d))),
+            ("homepage", self.homepage.as_ref().map(|h| json!(h))),
+            ("tags", self.tags.as_ref().map(|t| json!(t))),
+            ("visibility", self.visibility.as_ref().map(|v| json!(v))),
+        ];
+        for (key, value) in changes.iter() {
+            if let Some(value) = value {
+                body[key] = value.clone();
+            }
+        }
+        if changes.iter().all(|(_, value)| value.is_none()) {
+            return err(PolkahubError::Input(
+                "Nothing to change. Provide --description, --homepage, --tags or --visibility."
+                    .to_string(),
+            ));
+        }
+
+        println!("\nEditing {} project", name);
+        let response = self.post_request_with_token(PROJECT_EDIT_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle("Could not edit project", "done\n")
+    }
+
+    pub async fn delete_project(&self) -> Result<()> {
+        let name = self.args.first().cloned().unwrap_or_default();
+        check_zero_len(
+            &name,
+            "You must provide name of a project to delete.".into(),
+        )?;
+        check_name("Project", &name)?;
+        if !self.yes
+            && !confirm(&format!(
+                "Delete {} project with all its versions? This can not be undone",
+                name
+            ))?
+        {
+            println!("Aborted");
+            return Ok(());
+        }
+
+        let body = json!({
+            "project_name": name,
+        });
+        println!("\nDeleting {} project", name);
+        let response = self
+            .post_request_with_token(PROJECT_DELETE_URL, body)
+            .await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle("Could not delete project", "done\n")
+    }
+
+    pub async fn yank(&self) -> Result<()> {
+        let full_name = self.name.clone().unwrap_or_default();
+        let project_metadata = parse_full_name_project(&full_name)?;
+        let body = json!({
+            "login": project_metadata.login,
+            "project_name": project_metadata.name,
+            "version": project_metadata.version,
+        });
+        println!("\nYanking {}", full_name);
+        let response = self.post_request_with_token(YANK_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle("Could not yank version", "done\n")
+    }
+
+    pub async fn deprecate(&self) -> Result<()> {
+        let full_name = self.name.clone().unwrap_or_default();
+        let project_metadata = parse_full_name_project(&full_name)?;
+        let message = self.message.clone().unwrap_or_default();
+        check_zero_len(
+            &message,
+            "You must provide deprecation --message for users of this version.".into(),
+        )?;
+        let body = json!({
+            "login": project_metadata.login,
+            "project_name": project_metadata.name,
+            "version": project_metadata.version,
+            "message": message,
+        });
+        println!("\nDeprecating {}", full_name);
+        let response = self.post_request_with_token(DEPRECATE_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?
+            .handle("Could not deprecate version", "done\n")
+    }
+
+    pub async fn info(&self) -> Result<()> {
+        let reference = self.name.clone().unwrap_or_default();
+        let captures = match PROJECT_REF.captures(&reference) {
+            Some(c) => c,
+            None => {
+                return err(PolkahubError::Input(
+                    "You must provide project as <login>/<project_name>[@<version>]".to_string(),
+                ))
+            }
+        };
+        let version = captures.name("version").map(|v| v.as_str());
+        let body = json!({
+            "login": &captures["login"],
+            "project_name": &captures["name"],
+            "version": version,
+        });
+        if !self.json {
+            println!("\nLooking for {} project", reference);
+        }
+        let response = self.post_request_cached(INFO_URL, body).await?;
+        let mut response: InfoResponse = serde_json::from_str(&response)?;
+        if let (InfoResponse::OkResult { payload }, Some(version)) = (&mut response, version) {
+            payload.versions.retain(|v| v.version == version);
+            payload.deployments.retain(|a| a.version == version);
+            if payload.versions.is_empty() {
+                return err(PolkahubError::NotFound {
+                    context: "Could not get project info".into(),
+                    reason: format!("{} is not published", reference),
+                });
+            }
+        }
+        response.handle(self.json)
+    }
+
+    pub async fn create_org(&self) -> Result<()> {
+        let org = self.args.first().cloned().unwrap_or_default();
+        check_zero_len(
+            &org,
+            "You must provide name of organization to create.".into(),
+        )?;
+        check_name("Organization", &org)?;
+        let body = json!({
+            "org": org,
+        });
+        println!("\nCreating {} organization", org);
+        let response = self.post_request_with_token(ORG_CREATE_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?
+            .handle("Could not create organization", "done\n")
+    }
+
+    pub async fn add_org_member(&self) -> Result<()> {
+        let (org, user) = self.org_member_args()?;
+        let role = self.role.clone().unwrap_or_default();
+        check_zero_len(
+            &role,
+            "You must provide member --role: owner, publisher or deployer.".into(),
+        )?;
+        let body = json!({
+            "org": org,
+            "login": user,
+            "role": role,
+        });
+        println!("\nAdding {} to {} as {}", user, org, role);
+        let response = self
+            .post_request_with_token(ORG_ADD_MEMBER_URL, body)
+            .await?;
+        serde_json::from_str::<AckResponse>(&response)?
+            .handle("Could not add organization member", "done\n")
+    }
+
+    pub async fn remove_org_member(&self) -> Result<()> {
+        let (org, user) = self.org_member_args()?;
+        let body = json!({
+            "org": org,
+            "login": user,
+        });
+        println!("\nRemoving {} from {}", user, org);
+        let response = self
+            .post_request_with_token(ORG_REMOVE_MEMBER_URL, body)
+            .await?;
+        serde_json::from_str::<AckResponse>(&response)?
+            .handle("Could not remove organization member", "done\n")
+    }
+
+    pub async fn create_token(&self) -> Result<()> {
+        let name = self.token_name.clone().unwrap_or_default();
+        check_zero_len(&name, "You must provide token --name.".into())?;
+        let scope = self.scope.clone().unwrap_or_default();
+        if scope.is_empty() {
+            return err(PolkahubError::Input(
+                "You must provide token --scope, e.g. --scope install,find".to_string(),
+            ));
+        }
+        let expires_in = match &self.expires {
+            Some(expires) => Some(parse_lifetime(expires)?),
+            None => None,
+        };
+        let body = json!({
+            "name": name,
+            "scopes": scope,
+            "expires_in": expires_in,
+        });
+        println!("\nCreating {} token", name);
+        let response = self.post_request_with_token(TOKEN_CREATE_URL, body).await?;
+        serde_json::from_str::<TokenCreatedResponse>(&response)?.handle()
+    }
+
+    pub async fn list_tokens(&self) -> Result<()> {
+        println!("\nListing your tokens");
+        let response = self
+            .post_request_with_token(TOKEN_LIST_URL, json!({}))
+            .await?;
+        serde_json::from_str::<TokenListResponse>(&response)?.handle()
+    }
+
+    pub async fn revoke_token(&self) -> Result<()> {
+        let name = self.args.first().cloned().unwrap_or_default();
+        check_zero_len(&name, "You must provide name of a token to revoke.".into())?;
+        let body = json!({
+            "name": name,
+        });
+        println!("\nRevoking {} token", name);
+        let response = self.post_request_with_token(TOKEN_REVOKE_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle("Could not revoke token", "done\n")
+    }
+
+    pub async fn register(&self) -> Result<()> {
         let (email, password) = (read_email()?, read_password_with_confirmation()?);
         self.send_register_request(REGISTER_URL, &email, &password)
             .await?
-            .handle();
-        Ok(())
+            .handle()
     }
 
     pub async fn login(&self) -> Result<()> {
-        let (email, password) = (read_email()?, read_password()?);
-        self.send_login_request(LOGIN_URL, &email, &password)
-            .await?
-            .handle();
+        if self.device {
+            return self.device_login().await;
+        }
+        let (email, password) = (read_email()?, read_password("Password: ")?);
+        let mut response = self
+            .send_login_request(LOGIN_URL, &email, &password)
+            .await?;
+        if let LoginedResponse::TwoFactorRequired { payload } = &response {
+            let code = read_code("Authentication code: ")?;
+            let body = json!({
+                "session": payload.session,
+                "code": code,
+            });
+            response = serde_json::from_str(&self.post_request(LOGIN_2FA_URL, body).await?)?;
+        }
+        response.handle()
+    }
+
+    async fn device_login(&self) -> Result<()> {
+        let response = self.post_request(DEVICE_CODE_URL, json!({})).await?;
+        let code = match serde_json::from_str(&response)? {
+            DeviceCodeResponse::OkResult { payload } => payload,
+            DeviceCodeResponse::ErrResult { reason } => {
+                return err(PolkahubError::Auth(format!("Could not login: {}", reason)))
+            }
+        };
+        print!("\nOpen ");
+        print_blue(&code.verification_uri);
+        print!(" and enter the code ");
+        print_green(&code.user_code);
+        println!("\n");
+
+        let client = reqwest::Client::new();
+        let body = json!({
+            "device_code": code.device_code,
+        });
+        let mut interval = Duration::from_secs(code.interval);
+        let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+        let stage = Stage::start("Waiting for approval");
+        let result = loop {
+            if Instant::now() >= deadline {
+                break Err(PolkahubError::Auth(
+                    "Could not login: the code has expired".into(),
+                ));
+            }
+            delay_for(interval).await;
+            match poll_device_token(&client, &body).await {
+                Ok(DeviceTokenResponse::OkResult { payload }) => break Ok(payload.token),
+                Ok(DeviceTokenResponse::Pending) => {}
+                Ok(DeviceTokenResponse::SlowDown) => interval += Duration::from_secs(5),
+                Ok(DeviceTokenResponse::ErrResult { reason }) => {
+                    break Err(PolkahubError::Auth(format!("Could not login: {}", reason)))
+                }
+                Err(e) => break Err(e.into()),
+            }
+        };
+        drop(stage);
+
+        store_token(&result?)?;
+        print_green("done\n");
+        Ok(())
+    }
+
+    pub async fn change_password(&self) -> Result<()> {
+        let old_password = read_password("Old password: ")?;
+        println!("Enter new password");
+        let password = read_password_with_confirmation()?;
+        let body = json!({
+            "old_password": old_password,
+            "new_password": password,
+        });
+        println!("\nChanging password");
+        let response = self
+            .post_request_with_token(PASSWORD_CHANGE_URL, body)
+            .await?;
+        serde_json::from_str::<AckResponse>(&response)?
+            .handle("Could not change password", "done\n")
+    }
+
+    pub async fn reset_password(&self) -> Result<()> {
+        let email = read_email()?;
+        let body = json!({
+            "email": email,
+        });
+        println!("\nRequesting password reset for {}", email);
+        let response = self.post_request(PASSWORD_RESET_REQUEST_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle(
+            "Could not reset password",
+            "Reset code sent to your email\n",
+        )?;
+
+        let code = read_code("Code: ")?;
+        println!("Enter new password");
+        let password = read_password_with_confirmation()?;
+        let body = json!({
+            "email": email,
+            "code": code,
+            "password": password,
+        });
+        let response = self.post_request(PASSWORD_RESET_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle("Could not reset password", "done\n")
+    }
+
+    pub async fn verify(&self) -> Result<()> {
+        let email = read_email()?;
+        let code = match self.args.first() {
+            Some(code) => code.clone(),
+            None => read_code("Code: ")?,
+        };
+        let body = json!({
+            "email": email,
+            "code": code,
+        });
+        println!("\nVerifying {}", email);
+        let response = self.post_request(VERIFY_URL, body).await?;
+        serde_json::from_str::<AckResponse>(&response)?.handle("Could not verify email", "done\n")
+    }
+
+    pub async fn dev_server(&self) -> Result<()> {
+        let addr = self.name.as_deref().unwrap_or(DEV_SERVER_ADDR);
+        let addr = addr.parse().map_err(|e| {
+            PolkahubError::Input(format!("Invalid dev-server address {}: {}", addr, e))
+        })?;
+        dev_server::run(addr).await
+    }
+
+    pub fn completions(&self) -> Result<()> {
+        completions::generate(self.name.as_deref().unwrap_or(""), &mut io::stdout())
+    }
+
+    /// project references starting with `login/`, nothing is printed on failures
+    pub async fn complete(&self) -> Result<()> {
+        let prefix = self.name.clone().unwrap_or_default();
+        let (login, rest) = match prefix.find('/') {
+            Some(i) => (&prefix[..i], &prefix[i + 1..]),
+            None => return Ok(()),
+        };
+        let name = rest.split('@').next().unwrap_or(rest);
+        let body = json!({
+            "name": name,
+            "user": login,
+            "limit": COMPLETION_LIMIT,
+        });
+        // keyed by token as well, other accounts may see other projects
+        let key = cache_key(FIND_URL, &read_token().unwrap_or_default(), &body);
+        let (response, fetched) = match cache::read(&key, COMPLETION_CACHE_TTL) {
+            Some(data) => (data, false),
+            None => match self.post_request_with_token(FIND_URL, body).await {
+                Ok(data) => (data, true),
+                Err(_) => return Ok(()),
+            },
+        };
+        if let Ok(FoundResponse::OkResult { payload }) = serde_json::from_str(&response) {
+            if fetched {
+                let _ = cache::write(&key, &response);
+            }
+            let versions = payload
+                .iter()
+                .map(|p| (p.login.as_str(), p.name.as_str(), p.version.as_str()));
+            for candidate in completions::candidates(&prefix, versions) {
+                println!("{}", candidate);
+            }
+        }
         Ok(())
     }
 
+    pub async fn two_factor(&self) -> Result<()> {
+        match self.args.first().map(String::as_str) {
+            Some("enable") => {
+                println!("\nEnabling two-factor authentication");
+                let response = self
+                    .post_request_with_token(TWO_FACTOR_ENABLE_URL, json!({}))
+                    .await?;
+                serde_json::from_str::<TwoFactorEnabledResponse>(&response)?.handle()?;
+                let body = json!({
+                    "code": read_code("Authentication code: ")?,
+                });
+                let response = self
+                    .post_request_with_token(TWO_FACTOR_CONFIRM_URL, body)
+                    .await?;
+                serde_json::from_str::<AckResponse>(&response)?
+                    .handle("Could not confirm two-factor authentication", "done\n")
+            }
+            Some("disable") => {
+                let body = json!({
+                    "code": read_code("Authentication code: ")?,
+                });
+                println!("\nDisabling two-factor authentication");
+                let response = self
+                    .post_request_with_token(TWO_FACTOR_DISABLE_URL, body)
+                    .await?;
+                serde_json::from_str::<AckResponse>(&response)?
+                    .handle("Could not disable two-factor authentication", "done\n")
+            }
+            _ => err(PolkahubError::Input(
+                "You must choose `account 2fa enable` or `account 2fa disable`".to_string(),
+            )),
+        }
+    }
+
     pub fn parse_action(&self) -> Action {
-        let a_parsed = Action::from_str(&self.action);
+        // group actions take their subcommand as the next argument
+        let action = match (self.action.as_str(), &self.name) {
+            ("project", Some(sub))
+            | ("org", Some(sub))
+            | ("token", Some(sub))
+            | ("account", Some(sub))
+            | ("cache", Some(sub)) => {
+                format!("{} {}", self.action, sub)
+            }
+            _ => self.action.clone(),
+        };
+        let a_parsed = Action::from_str(&action);
         match a_parsed {
             Ok(action) => action,
-            Err(_) => Action::InputError(Failure {
-                status: "Input error".to_owned(),
-                reason: format!("{} - is invalid action. {}", self.action, HELP_NOTION),
-            }),
+            Err(_) => Action::InputError(PolkahubError::Input(format!(
+                "{} - is invalid action.",
+                self.action
+            ))),
         }
     }
 
     async fn send_create_request(&self, url: &str) -> Result<CreatedResponse> {
         let name = self.name.clone().unwrap_or_else(|| "".to_string());
         check_zero_len(&name, "You must provide name to create a project.".into())?;
-        check_project_name(&name)?;
+        check_name("Project", &name)?;
+        let hub = read_hubfile(self.hub_file.clone().unwrap_or_default()).await?;
+        if let Some(org) = &self.org {
+            check_name("Organization", org)?;
+        }
         let body = json!({
             "project_name": name,
+            "description": hub.parachain.map(|p| p.description),
+            "org": self.org,
         });
-        println!("\nCreating {} project", name);
+        match &self.org {
+            Some(org) => println!("\nCreating {}/{} project", org, name),
+            None => println!("\nCreating {} project", name),
+        }
         let response = self.post_request_with_token(url, body).await?;
         serde_json::from_str(&response).map_err(|e| e.into())
     }
 
-    async fn send_find_request(&self, url: &str) -> Result<FoundResponse> {
-        let name = self.name.clone().unwrap_or_else(|| "".to_string());
-        check_zero_len(&name, "You must provide a project name to look for.".into())?;
-
+    async fn send_search_request(&self, url: &str, query: &str) -> Result<FoundResponse> {
         let body = json!({
-            "name": name,
+            "name": query,
+            "user": self.user,
+            "limit": self.limit,
+            "page": self.page,
+            "sort": self.sort,
         });
 
-        println!("\nLooking for {} project", name);
+        match &self.user {
+            Some(user) => println!("\nLooking for {} projects of {}", query, user),
+            None => println!("\nLooking for {} projects", query),
+        }
+        let response = self.post_request_cached(url, body).await?;
+        serde_json::from_str(&response).map_err(|e| e.into())
+    }
+
+    async fn send_publish_request(&self, url: &str) -> Result<PublishedResponse> {
+        let hub = self.read_hub().await?;
+        let parachain = match hub.parachain {
+            Some(p) => p,
+            None => {
+                return err(PolkahubError::Input("You must describe [parachain] name, version and description in Hub.toml to publish a version."
+                        .to_string()))
+            }
+        };
+        let name = self.name.clone().unwrap_or_else(|| parachain.name.clone());
+        check_name("Project", &name)?;
+        check_zero_len(
+            &parachain.version,
+            "You must provide parachain version in Hub.toml to publish it.".into(),
+        )?;
+
+        if let Some(org) = &self.org {
+            check_name("Organization", org)?;
+        }
+        let full_name = match &self.org {
+            Some(org) => format!("{}/{}", org, name),
+            None => name.clone(),
+        };
+
+        // other owners may publish projects with the same name,
+        // so only the projects of the publishing owner are checked
+        let response = self
+            .post_request_with_token(LIST_URL, json!({ "org": self.org }))
+            .await?;
+        match serde_json::from_str(&response)? {
+            ListResponse::OkResult { payload } => {
+                if payload.projects.iter().any(|p| {
+                    let owned = match &self.org {
+                        Some(org) => &p.login == org,
+                        None => true,
+                    };
+                    owned && p.name == name && p.versions.contains(&parachain.version)
+                }) {
+                    return err(PolkahubError::Input(format!(
+                        "Version {} of {} project is already published. Bump version in Hub.toml first.",
+                        parachain.version, full_name
+                    )));
+                }
+            }
+            ListResponse::ErrResult { reason } => {
+                return err(PolkahubError::server(
+                    "Could not check published versions",
+                    reason,
+                ));
+            }
+        }
+
+        let chainspec_hash = match &hub.chainspec {
+            Some(chainspec) => Some(
+                self.upload_chainspec(&name, &parachain.version, chainspec)
+                    .await?,
+            ),
+            None => None,
+        };
+        let body = json!({
+            "project_name": name,
+            "version": parachain.version,
+            "description": parachain.description,
+            "chainspec_hash": chainspec_hash,
+            "org": self.org,
+        });
+        println!(
+            "\nPublishing {} project with version {}",
+            full_name, parachain.version
+        );
         let response = self.post_request_with_token(url, body).await?;
         serde_json::from_str(&response).map_err(|e| e.into())
     }
 
     async fn send_install_request(&self, url: &str) -> Result<InstalledResponse> {
-        let project_metadata = self.parse_full_name_project()?;
+        let full_name = self.name.clone().unwrap_or_else(|| "".to_string());
+        let project_metadata = parse_full_name_project(&full_name)?;
         let (name, version) = self.persist_hub(&project_metadata).await?;
-        check_project_name(&name)?;
+        check_name("App", &name)?;
+        self.check_not_yanked(&project_metadata).await?;
 
         let body = json!({
             "app_name": name,
@@ -436,6 +2141,79 @@ impl Project {
         serde_json::from_str(&response).map_err(|e| e.into())
     }
 
+    /// refuse yanked versions unless --allow-yanked, warn about deprecated ones
+    async fn check_not_yanked(&self, project_metadata: &ProjectMetadata) -> Result<()> {
+        let body = json!({
+            "login": project_metadata.login,
+            "project_name": project_metadata.name,
+            "version": project_metadata.version,
+        });
+        let reply = send_request(self.authorized_client()?, INFO_URL, body, None).await?;
+        // let install request report missing project
+        if reply.status == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        let info = match serde_json::from_str::<InfoResponse>(&reply.body)? {
+            InfoResponse::OkResult { payload } => payload,
+            InfoResponse::ErrResult { reason } => {
+                return err(PolkahubError::server("Could not check version", reason))
+            }
+        };
+        let found = info
+            .versions
+            .iter()
+            .find(|v| v.version == project_metadata.version);
+        match found {
+            Some(v) if v.yanked && !self.allow_yanked => err(PolkahubError::Input(format!(
+                "{}/{}@{} is yanked. Use --allow-yanked to install it anyway.",
+                info.login, info.name, v.version
+            ))),
+            Some(v) => {
+                if let Some(message) = &v.deprecated {
+                    print_warning(&format!("{} is deprecated: {}", v.version, message));
+                }
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn send_status_request(&self, url: &str, name: &str) -> Result<StatusResponse> {
+        let body = json!({
+            "app_name": name,
+        });
+        let response = self.post_request_with_token(url, body).await?;
+        serde_json::from_str(&response).map_err(|e| e.into())
+    }
+
+    async fn send_list_request(&self, url: &str) -> Result<ListResponse> {
+        if !self.json {
+            println!("\nListing your projects and apps");
+        }
+        let response = self.post_request_with_token(url, json!({})).await?;
+        serde_json::from_str(&response).map_err(|e| e.into())
+    }
+
+    async fn send_uninstall_request(&self, url: &str, name: &str) -> Result<AckResponse> {
+        let body = json!({
+            "app_name": name,
+        });
+        println!("\nUninstalling {} app", name);
+        let response = self.post_request_with_token(url, body).await?;
+        serde_json::from_str(&response).map_err(|e| e.into())
+    }
+
+    async fn send_upgrade_request(
+        &self,
+        url: &str,
+        name: &str,
+        body: Value,
+    ) -> Result<UpgradedResponse> {
+        println!("\nUpgrading {} app", name);
+        let response = self.post_request_with_token(url, body).await?;
+        serde_json::from_str(&response).map_err(|e| e.into())
+    }
+
     async fn send_register_request(
         &self,
         url: &str,
@@ -451,6 +2229,69 @@ impl Project {
         serde_json::from_str(&response).map_err(|e| e.into())
     }
 
+    /// upload chainspec file from Hub.toml, returns its hash
+    async fn upload_chainspec(
+        &self,
+        name: &str,
+        version: &str,
+        chainspec: &Chainspec,
+    ) -> Result<String> {
+        // chainspec path is relative to Hub.toml
+        let hub_dir = self.hub_file.as_deref().unwrap_or("");
+        let hub_dir = hub_dir.split("Hub.toml").next().unwrap_or(hub_dir);
+        let path = Path::new(hub_dir).join(&chainspec.path);
+        let file = File::open(&path).await?;
+        let size = file.metadata().await?.len();
+
+        println!("\nUploading chainspec {}", path.display());
+        let upload = Upload::start("Uploading chainspec", size);
+        let progress = upload.clone();
+        // the file is read and the bar moves as hyper pulls chunks of the body
+        let chunks = stream::unfold(Some(file), |file| async move {
+            let mut file = file?;
+            let mut chunk = vec![0; UPLOAD_CHUNK_SIZE];
+            match file.read(&mut chunk).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    chunk.truncate(n);
+                    Some((Ok(chunk), Some(file)))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        let body = reqwest::Body::wrap_stream(chunks.map(move |chunk: io::Result<Vec<u8>>| {
+            if let Ok(chunk) = &chunk {
+                progress.inc(chunk.len() as u64);
+            }
+            chunk
+        }));
+        let url = api_url(CHAINSPEC_UPLOAD_URL);
+        debug!(%url, bytes = size, "POST chainspec");
+        let response = self
+            .authorized_client()?
+            .post(&url)
+            .query(&[
+                ("project_name", name),
+                ("version", version),
+                ("chainspec_version", &chainspec.version),
+            ])
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_LENGTH, size)
+            .body(body)
+            .send()
+            .await;
+        upload.finish();
+        let response = response?.text().await?;
+        trace!(%url, body = %logging::redact_text(&response), "response body");
+
+        match serde_json::from_str(&response)? {
+            ChainspecUploadedResponse::OkResult { payload } => Ok(payload.hash),
+            ChainspecUploadedResponse::ErrResult { reason } => {
+                err(PolkahubError::server("Could not upload chainspec", reason))
+            }
+        }
+    }
+
     async fn send_login_request(
         &self,
         url: &str,
@@ -467,89 +2308,84 @@ impl Project {
     }
 
     async fn post_request(&self, url: &str, body: Value) -> Result<String> {
-        let client = reqwest::Client::new();
-        let mut loader = Infinite::new().to_stderr();
-        loader.set_msg("");
-
-        let _ = loader.start();
-        let result = client.post(url).json(&body).send().await?.text().await?;
-        let _ = loader.stop();
-
-        Ok(result)
+        send_post(reqwest::Client::new(), url, body).await
     }
 
     async fn post_request_with_token(&self, url: &str, body: Value) -> Result<String> {
-        let token = read_token().map_err(|e| {
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("{:?}. Invalid token, please registered and auth first.", e),
-            )
-        })?;
-        let mut headers = header::HeaderMap::new();
-        let auth_data =
-            header::HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|e| {
-                std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("{:?}. Invalid token, please registered and auth first.", e),
-                )
-            })?;
-        headers.insert(header::AUTHORIZATION, auth_data);
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-        let mut loader = Infinite::new().to_stderr();
-        loader.set_msg("");
-
-        let _ = loader.start();
-        let result = client.post(url).json(&body).send().await?.text().await?;
-        let _ = loader.stop();
-
-        Ok(result)
+        send_post(self.authorized_client()?, url, body).await
     }
 
-    fn parse_full_name_project(&self) -> Result<ProjectMetadata, anyhow::Error> {
-        let s = self.name.clone().unwrap_or_else(|| "".to_string());
-        let f = Failure {
-            status: "Input error".to_owned(),
-            reason:
-                "You must provide specific version to install: <login>/<project_name>@<version>"
-                    .to_string(),
-        };
-        if let Some(captures) = PROJECT_FULL_NAME.captures(&s) {
-            let login = match captures.name("login") {
-                Some(l) => l.as_str().to_string(),
-                None => return failure_to_anyhow::<ProjectMetadata>(f),
+    /// request answered from the local cache with --offline, otherwise
+    /// revalidated with the ETag of the cached response
+    async fn post_request_cached(&self, url: &str, body: Value) -> Result<String> {
+        let key = cache_key(url, &read_token().unwrap_or_default(), &body);
+        let cached = cache::load(&key);
+        if self.offline {
+            return match cached {
+                Some(entry) => Ok(entry.body),
+                None => err(PolkahubError::Network(
+                    "Nothing cached for this request, run it once without --offline".into(),
+                )),
             };
-            let name = match captures.name("name") {
-                Some(n) => n.as_str().to_string(),
-                None => return failure_to_anyhow::<ProjectMetadata>(f),
-            };
-            let version = match captures.name("version") {
-                Some(v) => v.as_str().to_string(),
-                None => return failure_to_anyhow::<ProjectMetadata>(f),
-            };
-            Ok(ProjectMetadata {
-                login,
-                name,
-                version,
-            })
-        } else {
-            failure_to_anyhow::<ProjectMetadata>(f)
         }
+
+        let etag = cached.as_ref().and_then(|e| e.etag.clone());
+        let reply = send_request(self.authorized_client()?, url, body, etag.as_deref()).await?;
+        match cached {
+            Some(entry) if reply.status == StatusCode::NOT_MODIFIED => Ok(entry.body),
+            _ => {
+                let is_ok = serde_json::from_str::<Value>(&reply.body)
+                    .ok()
+                    .filter(|v| v["status"] == "ok")
+                    .is_some();
+                if reply.status.is_success() && is_ok {
+                    let entry = cache::Entry {
+                        etag: reply.etag,
+                        body: reply.body,
+                    };
+                    cache::store(&key, &entry)?;
+                    return Ok(entry.body);
+                }
+                Ok(reply.body)
+            }
+        }
+    }
+
+    pub fn clear_cache(&self) -> Result<()> {
+        println!(
+            "\nClearing cache in {}",
+            polkahub_home_path().join("cache").display()
+        );
+        cache::clear()?;
+        print_green("done\n");
+        Ok(())
+    }
+
+    fn authorized_client(&self) -> Result<reqwest::Client> {
+        let token = read_token().map_err(|e| {
+            PolkahubError::Auth(format!(
+                "{}. Invalid token, please registered and auth first.",
+                e
+            ))
+        })?;
+        let mut headers = header::HeaderMap::new();
+        let auth_data =
+            header::HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|e| {
+                PolkahubError::Auth(format!(
+                    "{}. Invalid token, please registered and auth first.",
+                    e
+                ))
+            })?;
+        headers.insert(header::AUTHORIZATION, auth_data);
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()?;
+        Ok(client)
     }
 
     /// if Hub.toml is present, use its data over flags
     async fn persist_hub(&self, project_metadata: &ProjectMetadata) -> Result<(String, String)> {
-        let hub_file = self.hub_file.clone().unwrap_or_else(|| {
-            // print warning if you provide an alias but have name in Hub.toml
-            // (priority concerns)
-            if self.alias.is_none() {
-                print_yellow("WARN: ");
-                print_italic("No Hub.toml path provided, looking in root directory\n");
-            }
-            "".to_string()
-        });
-        let hub = read_hubfile(hub_file).await?;
+        let hub = self.read_hub().await?;
         // if hub exist take values from there
         let (app_name, version) = if let Some(p) = hub.parachain {
             (p.name, p.version)
@@ -566,6 +2402,95 @@ impl Project {
         };
         Ok((app_name, version))
     }
+
+    async fn wait_for_node(&self, http_url: &str) -> Result<()> {
+        println!("\nWaiting for node at {}", http_url);
+        let stage = Stage::start("Waiting for node");
+        let result = rpc::wait_for_ready(
+            http_url,
+            Duration::from_secs(self.timeout),
+            NODE_POLL_INTERVAL,
+        )
+        .await;
+        drop(stage);
+
+        match result {
+            Ok(summary) => {
+                print_green(&format!(
+                    "ready in {}\n",
+                    format_duration(summary.elapsed.as_secs())
+                ));
+                print_blue("chain  ");
+                println!(" -> {}", summary.chain);
+                print_blue("peers  ");
+                println!(" -> {}", summary.health.peers);
+                print_blue("syncing");
+                println!(" -> {}", summary.health.is_syncing);
+                Ok(())
+            }
+            Err(e) => err(PolkahubError::Network(format!("Node is not ready: {}", e))),
+        }
+    }
+
+    fn add_git_remote(&self, repo_url: &str) -> Result<()> {
+        match git::add_remote(repo_url)? {
+            git::RemoteStatus::Added => {
+                print_italic("git remote");
+                println!(" -> {} added", git::REMOTE_NAME);
+            }
+            git::RemoteStatus::Exists(url) if url == repo_url => {
+                print_warning(&format!(
+                    "git remote `{}` already exists -> {}",
+                    git::REMOTE_NAME,
+                    url
+                ));
+            }
+            git::RemoteStatus::Exists(url) => {
+                print_warning(&format!(
+                    "git remote `{}` points to {}, not to {}",
+                    git::REMOTE_NAME,
+                    url,
+                    repo_url
+                ));
+                if self.push {
+                    return err(PolkahubError::Config(format!(
+                        "Refusing to push to {}, update the remote with `git remote set-url {} {}`",
+                        url,
+                        git::REMOTE_NAME,
+                        repo_url
+                    )));
+                }
+            }
+        }
+        if self.push {
+            let branch = git::push_current_branch()?;
+            print_italic("pushed");
+            println!("     -> {} to {}", branch, git::REMOTE_NAME);
+        }
+        Ok(())
+    }
+
+    /// <org> <user> arguments of org member actions
+    fn org_member_args(&self) -> Result<(String, String)> {
+        let org = self.args.first().cloned().unwrap_or_default();
+        let user = self.args.get(1).cloned().unwrap_or_default();
+        check_zero_len(&org, "You must provide organization name.".into())?;
+        check_zero_len(&user, "You must provide login of a member.".into())?;
+        check_name("Organization", &org)?;
+        Ok((org, user))
+    }
+
+    async fn read_hub(&self) -> Result<Hub> {
+        let hub_file = self.hub_file.clone().unwrap_or_else(|| {
+            // print warning if you provide an alias but have name in Hub.toml
+            // (priority concerns)
+            if self.alias.is_none() {
+                print_warning("No Hub.toml path provided, looking in root directory");
+            }
+            "".to_string()
+        });
+        read_hubfile(hub_file).await
+    }
 }
 
 pub fn print_help() -> Result<()> {
@@ -573,52 +2498,306 @@ pub fn print_help() -> Result<()> {
     print_blue("help ");
     println!(" - list all possible options");
     print_blue("install ");
-    println!(" - launch parachain node");
+    println!(" - launch parachain node (--wait to wait until it is healthy)");
     print_blue("find ");
-    println!(" - find all versions of your project");
+    println!(" - find project versions (--user, --limit, --page, --sort version|date)");
     print_blue("create ");
-    println!(" - register new parachain and create endpoints");
+    println!(
+        " - register new parachain and create endpoints (--git to add remote, --push to push)"
+    );
+    print_blue("publish ");
+    println!(" - publish version from Hub.toml (--tag to tag git repository)");
+    print_blue("status ");
+    println!(" - show deployment state of your app (--watch to wait until it settles)");
+    print_blue("list ");
+    println!(" - list your projects and installed apps (--project, --sort, --json)");
+    print_blue("uninstall ");
+    println!(" - remove installed app (--yes to skip confirmation, --dry-run)");
+    print_blue("upgrade ");
+    println!(" - move installed app to another version: upgrade <app_name> <login/name@version> (--rollback)");
+    print_blue("logs ");
+    println!(" - show node logs of installed app (--follow, --since, --tail N, --grep)");
+    print_blue("project edit ");
+    println!(" - change project <name> --description, --homepage, --tags or --visibility");
+    print_blue("project delete ");
+    println!(" - delete project <name> with all its versions");
+    print_blue("yank ");
+    println!(" - forbid installing <login>/<name>@<version> (unless --allow-yanked)");
+    print_blue("deprecate ");
+    println!(" - mark <login>/<name>@<version> deprecated with --message");
+    print_blue("info ");
+    println!(" - show details of <login>/<name>[@<version>] (--json)");
+    print_blue("org create ");
+    println!(" - create organization <org> to share projects (create <name> --org <org>)");
+    print_blue("org add-member ");
+    println!(" - add <org> <login> member with --role owner|publisher|deployer");
+    print_blue("org remove-member ");
+    println!(" - remove <org> <login> member");
+    print_blue("token create ");
+    println!(" - create api token for CI: --name <name> --scope install,find [--expires 30d]");
+    print_blue("token list ");
+    println!(" - list your api tokens");
+    print_blue("token revoke ");
+    println!(" - revoke api token <name>");
     print_blue("register ");
     println!(" - create a new user in Polkahub");
     print_blue("auth ");
-    println!(" - log in to Polkahub");
+    println!(" - log in to Polkahub, with --device approve the login in a browser");
+    print_blue("account password ");
+    println!(" - change your password");
+    print_blue("account reset-password ");
+    println!(" - reset forgotten password with a code sent to your email");
+    print_blue("account verify ");
+    println!(" - verify your email with <code> received after register");
+    print_blue("account 2fa ");
+    println!(" - enable or disable two-factor authentication");
+    print_blue("cache clear ");
+    println!(" - remove cached find and info results (--offline reads them)");
+    print_blue("completions ");
+    println!(" - print completion script for bash, zsh or fish");
+    print_blue("dev-server ");
+    println!(
+        " - serve a local in-memory api on [address], default {}",
+        DEV_SERVER_ADDR
+    );
+    Ok(())
+}
+
+pub fn err<O>(e: PolkahubError) -> Result<O> {
+    Err(e.into())
+}
+
+/// pass lines of streamed logs matching `grep` to `on_line`
+async fn stream_log_lines<F: FnMut(&str)>(
+    response: &mut reqwest::Response,
+    grep: Option<&Regex>,
+    mut on_line: F,
+) -> Result<()> {
+    let mut emit = |bytes: &[u8]| {
+        let line = String::from_utf8_lossy(bytes);
+        let line = line.trim_end();
+        if let Some(re) = grep {
+            if !re.is_match(line) {
+                return;
+            }
+        }
+        on_line(line);
+    };
+    // log lines may be split between chunks
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            emit(&line);
+        }
+    }
+    if !buffer.is_empty() {
+        emit(&buffer);
+    }
     Ok(())
 }
 
-pub fn err<O>(e: Failure) -> Result<O> {
-    let frame: String = e.status.chars().map(|_| '—').collect();
-    println!(" {}", frame);
-    print_red(&format!(" {}\n", e.status));
-    println!(" {}", frame);
-    println!("{}", e.reason);
-    failure_to_anyhow(e)
+fn print_log_line(line: &str) {
+    if line.contains("ERROR") {
+        print_red(line);
+    } else if line.contains("WARN") {
+        print_yellow(line);
+    } else if line.contains("DEBUG") || line.contains("TRACE") {
+        print_italic(line);
+    } else {
+        print!("{}", line);
+    }
+    println!();
+}
+
+/// print `old -> new` line if value changed, returns whether it did
+fn print_change(label: &str, old: &str, new: &str) -> bool {
+    print_blue(label);
+    if old == new {
+        println!(" {} (unchanged)", old);
+        false
+    } else {
+        print!(" ");
+        print_red(old);
+        print!(" -> ");
+        print_green(new);
+        println!();
+        true
+    }
+}
+
+/// print rows aligned by the widest cell of each column,
+/// last column is truncated to fit terminal width
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    if let (Ok((columns, _)), Some((last, rest))) =
+        (termion::terminal_size(), widths.split_last_mut())
+    {
+        let used: usize = rest.iter().map(|w| w + 2).sum();
+        *last = (*last).min(
+            (columns as usize)
+                .saturating_sub(used)
+                .max(MIN_COLUMN_WIDTH),
+        );
+    }
+    let rows = rows.into_iter().map(|row| {
+        row.into_iter()
+            .zip(&widths)
+            .map(|(cell, width)| truncate(cell, *width))
+            .collect::<Vec<_>>()
+    });
+    for (header, width) in headers.iter().zip(&widths) {
+        print_blue(&format!("{:<w$}  ", header, w = width));
+    }
+    println!();
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<w$}", cell, w = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+/// date part of RFC 3339 timestamp
+fn format_date(timestamp: Option<&String>) -> String {
+    timestamp
+        .map(|t| t.chars().take(10).collect())
+        .unwrap_or_default()
+}
+
+fn version_status(yanked: bool, deprecated: Option<&String>) -> String {
+    if yanked {
+        "yanked".to_string()
+    } else if let Some(message) = deprecated {
+        format!("deprecated: {}", message)
+    } else {
+        "".to_string()
+    }
+}
+
+fn truncate(s: String, width: usize) -> String {
+    if s.chars().count() <= width {
+        s
+    } else {
+        let mut truncated: String = s.chars().take(width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// semver ordering with fallback to numeric comparison of dot separated parts
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => {
+            let parts = |v: &str| -> Vec<(u64, String)> {
+                v.split(&['.', '-'][..])
+                    .map(|p| (p.parse().unwrap_or(0), p.to_string()))
+                    .collect()
+            };
+            parts(a).cmp(&parts(b))
+        }
+    }
+}
+
+fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes, seconds) = (
+        secs / 86400,
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    );
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// seconds in lifetime like 12h, 30d or 4w
+fn parse_lifetime(s: &str) -> Result<u64> {
+    let (amount, unit) = match s.char_indices().last() {
+        Some((i, unit)) => (&s[..i], unit),
+        None => ("", ' '),
+    };
+    let multiplier = match unit {
+        'h' => 3600,
+        'd' => 86400,
+        'w' => 7 * 86400,
+        _ => 0,
+    };
+    let seconds = match amount.parse::<u64>() {
+        Ok(amount) if amount > 0 => amount.checked_mul(multiplier),
+        _ => None,
+    };
+    match seconds {
+        Some(seconds) if seconds > 0 => Ok(seconds),
+        _ => err(PolkahubError::Input(format!(
+            "{} - is invalid lifetime, use hours, days or weeks, e.g. 12h, 30d, 4w",
+            s
+        ))),
+    }
 }
 
-fn failure_to_anyhow<O>(e: Failure) -> Result<O> {
-    Err(anyhow!("{}", e.reason))
+fn parse_full_name_project(s: &str) -> Result<ProjectMetadata, anyhow::Error> {
+    let f = || {
+        err(PolkahubError::Input(
+            "You must provide specific project version: <login or org>/<project_name>@<version>"
+                .to_string(),
+        ))
+    };
+    if let Some(captures) = PROJECT_FULL_NAME.captures(s) {
+        let login = match captures.name("login") {
+            Some(l) => l.as_str().to_string(),
+            None => return f(),
+        };
+        let name = match captures.name("name") {
+            Some(n) => n.as_str().to_string(),
+            None => return f(),
+        };
+        let version = match captures.name("version") {
+            Some(v) => v.as_str().to_string(),
+            None => return f(),
+        };
+        Ok(ProjectMetadata {
+            login,
+            name,
+            version,
+        })
+    } else {
+        f()
+    }
 }
 
 fn check_zero_len(s: &str, reason: String) -> Result<()> {
     if s.is_empty() {
-        let f = Failure {
-            status: "Input error".to_owned(),
-            reason,
-        };
-        err::<()>(f)
+        err(PolkahubError::Input(reason))
     } else {
         Ok(())
     }
 }
 
-fn check_project_name(project_name: &str) -> Result<()> {
-    if PROJECT_NAME.is_match(project_name) {
+/// projects, apps and organizations share the naming rules, `kind` names which one is checked
+fn check_name(kind: &str, name: &str) -> Result<()> {
+    if PROJECT_NAME.is_match(name) {
         Ok(())
     } else {
-        let f = Failure {
-            status: "Input error".to_string(),
-            reason: "Project name must consist only from 'a'-'z' '0'-'9', '-'.".to_string(),
-        };
-        failure_to_anyhow::<()>(f)
+        err(PolkahubError::Input(format!(
+            "{} name must consist only from 'a'-'z' '0'-'9', '-'.",
+            kind
+        )))
     }
 }
 
@@ -645,48 +2824,132 @@ fn parse_toml(f: &str) -> Hub {
 }
 
 fn read_email() -> Result<String> {
+    read_with_retries(|| {
+        let mut stream = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
+        write!(stream, "Email: ")?;
+        stream.flush()?;
+        let mut email = String::new();
+        std::io::stdin().read_line(&mut email)?;
+        let email = email.trim().to_string();
+        Ok(check_email(&email).map(|()| email))
+    })
+}
+
+fn read_code(prompt: &str) -> Result<String> {
     let mut stream = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
-    write!(stream, "Email: ")?;
+    write!(stream, "{}", prompt)?;
     stream.flush()?;
-    let mut email = String::new();
-    std::io::stdin().read_line(&mut email)?;
-    let email = email.trim();
-    if !&email.contains('@') {
-        let msg = "Email is invalid".to_string();
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, msg).into());
-    }
-    Ok(email.to_string())
+    let mut code = String::new();
+    std::io::stdin().read_line(&mut code)?;
+    let code = code.trim();
+    check_zero_len(code, "You must provide the code.".into())?;
+    Ok(code.to_string())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    let mut stream = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
+    write!(stream, "{} [y/N]: ", question)?;
+    stream.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
 
 fn read_password_with_confirmation() -> Result<String> {
-    let password = rpassword::read_password_from_tty(Some("Password: ")).unwrap();
-    let confirm_password = rpassword::read_password_from_tty(Some("Confirm Password: ")).unwrap();
-    if password.len() < MIN_PASSWORD_LENGTH {
-        let msg = format!("Password shorter than {} characters", MIN_PASSWORD_LENGTH);
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, msg).into());
+    read_with_retries(|| {
+        let password = rpassword::read_password_from_tty(Some("Password: "))?;
+        if let Err(reason) = check_password_length(&password) {
+            return Ok(Err(reason));
+        }
+        print_strength(&password::estimate(&password));
+        let confirm_password = rpassword::read_password_from_tty(Some("Confirm Password: "))?;
+        if password != confirm_password {
+            return Ok(Err("Password does not equal Confirm password".to_string()));
+        }
+        Ok(Ok(password))
+    })
+}
+
+fn read_password(prompt: &str) -> Result<String> {
+    read_with_retries(|| {
+        let password = rpassword::read_password_from_tty(Some(prompt))?;
+        Ok(check_password_length(&password).map(|()| password))
+    })
+}
+
+/// Repeat `read` while it returns invalid input, up to MAX_INPUT_ATTEMPTS times.
+/// `read` returns Err on io failures and Ok(Err(reason)) on invalid input.
+fn read_with_retries<F>(mut read: F) -> Result<String>
+where
+    F: FnMut() -> Result<Result<String, String>>,
+{
+    let mut attempt = 1;
+    loop {
+        match read()? {
+            Ok(value) => return Ok(value),
+            Err(reason) if attempt < MAX_INPUT_ATTEMPTS => {
+                eprint_red(&format!("{}, try again\n", reason));
+                attempt += 1;
+            }
+            Err(reason) => return err(PolkahubError::Input(reason)),
+        }
     }
-    if password.len() > MAX_PASSWORD_LENGTH {
-        let msg = format!("Password longer than {} characters", MAX_PASSWORD_LENGTH);
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, msg).into());
+}
+
+fn check_email(email: &str) -> Result<(), String> {
+    let local_len = email.split('@').next().map_or(0, str::len);
+    if email.len() > MAX_EMAIL_LENGTH
+        || local_len > MAX_EMAIL_LOCAL_LENGTH
+        || !EMAIL.is_match(email)
+    {
+        Err(format!("{} - is invalid email", email))
+    } else {
+        Ok(())
     }
-    if password != confirm_password {
-        let msg = "Password does not equal Confirm password".to_string();
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, msg).into());
+}
+
+/// length is counted in characters, not bytes
+fn check_password_length(password: &str) -> Result<(), String> {
+    let length = password.chars().count();
+    if length < MIN_PASSWORD_LENGTH {
+        Err(format!(
+            "Password shorter than {} characters",
+            MIN_PASSWORD_LENGTH
+        ))
+    } else if length > MAX_PASSWORD_LENGTH {
+        Err(format!(
+            "Password longer than {} characters",
+            MAX_PASSWORD_LENGTH
+        ))
+    } else {
+        Ok(())
     }
-    Ok(password)
 }
 
-fn read_password() -> Result<String> {
-    let password = rpassword::read_password_from_tty(Some("Password: ")).unwrap();
-    if password.len() < MIN_PASSWORD_LENGTH {
-        let msg = format!("Password shorter than {} characters", MIN_PASSWORD_LENGTH);
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, msg).into());
+fn print_strength(strength: &password::Strength) {
+    let score = strength.level.score();
+    let meter = format!("{}{}", "■".repeat(score), "□".repeat(5 - score));
+    print!("Strength: ");
+    match strength.level {
+        password::Level::VeryWeak | password::Level::Weak => print_red(&meter),
+        password::Level::Reasonable => print_yellow(&meter),
+        password::Level::Strong | password::Level::VeryStrong => print_green(&meter),
     }
-    if password.len() > MAX_PASSWORD_LENGTH {
-        let msg = format!("Password longer than {} characters", MAX_PASSWORD_LENGTH);
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, msg).into());
+    println!(" {} (~{:.0} bits)", strength.level.name(), strength.bits);
+    for hint in &strength.hints {
+        print_italic(&format!("  hint: {}\n", hint));
     }
-    Ok(password)
+}
+
+async fn poll_device_token(client: &reqwest::Client, body: &Value) -> Result<DeviceTokenResponse> {
+    let response = client
+        .post(&api_url(DEVICE_TOKEN_URL))
+        .json(body)
+        .send()
+        .await?
+        .text()
+        .await?;
+    Ok(serde_json::from_str(&response)?)
 }
 
 fn store_token(token: &str) -> Result<()> {
@@ -703,6 +2966,10 @@ fn store_token(token: &str) -> Result<()> {
 }
 
 fn read_token() -> Result<String> {
+    match env::var("POLKAHUB_TOKEN") {
+        Ok(token) if !token.is_empty() => return Ok(token),
+        _ => {}
+    }
     let file_path = polkahub_home_path().join("config");
     let mut file = std::fs::File::open(&file_path)?;
     let mut data = String::new();
@@ -710,10 +2977,760 @@ fn read_token() -> Result<String> {
     Ok(toml::from_str::<PolkahubConfig>(&data)?.token)
 }
 
-fn polkahub_home_path() -> PathBuf {
+/// `<endpoint>-<sha256 of token and body>`, stable between runs and apart for each account
+fn cache_key(url: &str, token: &str, body: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input(token.as_bytes());
+    hasher.input(b"\n");
+    hasher.input(body.to_string().as_bytes());
+    let digest: String = hasher
+        .result()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let endpoint = url.rsplit('/').next().unwrap_or(url);
+    format!("{}-{}", endpoint, digest)
+}
+
+/// text shown next to the spinner while the request is running
+fn stage_for(url: &str) -> &'static str {
+    match url {
+        LOGIN_URL | LOGIN_2FA_URL | DEVICE_CODE_URL => "Authenticating",
+        REGISTER_URL => "Registering",
+        CREATE_URL => "Creating project",
+        PUBLISH_URL => "Publishing",
+        INSTALL_URL => "Deploying",
+        UPGRADE_URL => "Upgrading",
+        UNINSTALL_URL => "Uninstalling",
+        FIND_URL | INFO_URL => "Searching",
+        STATUS_URL => "Checking status",
+        _ => "Waiting for Polkahub",
+    }
+}
+
+async fn send_post(client: reqwest::Client, url: &str, body: Value) -> Result<String> {
+    Ok(send_request(client, url, body, None).await?.body)
+}
+
+/// raw http reply, needed to revalidate cached responses
+struct Reply {
+    status: StatusCode,
+    etag: Option<String>,
+    body: String,
+}
+
+async fn send_request(
+    client: reqwest::Client,
+    url: &str,
+    body: Value,
+    if_none_match: Option<&str>,
+) -> Result<Reply> {
+    let stage = Stage::start(stage_for(url));
+    let url = api_url(url);
+    debug!(%url, body = %logging::redact(&body), ?if_none_match, "POST");
+    let started = Instant::now();
+    let mut request = client.post(&url).json(&body);
+    if let Some(etag) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    let response = request.send().await;
+    let result = match response {
+        Ok(response) => {
+            let status = response.status();
+            let etag = response
+                .headers()
+                .get(header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            let text = response.text().await;
+            debug!(%url, %status, elapsed = ?started.elapsed(), "response");
+            if let Ok(text) = &text {
+                trace!(%url, body = %logging::redact_text(text), "response body");
+            }
+            text.map(|body| Reply { status, etag, body })
+        }
+        Err(e) => {
+            debug!(%url, error = %e, elapsed = ?started.elapsed(), "request failed");
+            Err(e)
+        }
+    };
+    drop(stage);
+
+    let reply = result?;
+    // reqwest does not turn error statuses into errors, so an expired
+    // or revoked token has to be caught here
+    if reply.status == StatusCode::UNAUTHORIZED || reply.status == StatusCode::FORBIDDEN {
+        return err(PolkahubError::Auth(format!(
+            "Polkahub rejected the credentials with status {}",
+            reply.status
+        )));
+    }
+    Ok(reply)
+}
+
+/// points an endpoint to `POLKAHUB_API_URL`, e.g. a local mock server
+fn api_url(url: &str) -> String {
+    match env::var("POLKAHUB_API_URL") {
+        Ok(base) if url.starts_with(API_URL) => {
+            format!("{}{}", base.trim_end_matches('/'), &url[API_URL.len()..])
+        }
+        _ => url.to_string(),
+    }
+}
+
+pub(crate) fn polkahub_home_path() -> PathBuf {
     if let Ok(polkahub_home) = env::var("POLKAHUB_HOME") {
         return Path::new(&polkahub_home).to_owned();
     }
     let home = env::var("HOME").expect("please set environment variable $HOME");
     Path::new(&home).join(".polkahub")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev_server, stub};
+    use hyper::{Body, Response};
+    use std::sync::{Arc, Mutex};
+
+    const TOKEN: &str = "test-token";
+
+    fn project(args: &[&str]) -> Project {
+        Project::from_iter(std::iter::once("polkahub").chain(args.iter().cloned()))
+    }
+
+    /// server streaming `chunks` as the body of any request
+    fn streaming(chunks: &'static [&'static str]) -> String {
+        stub::serve(move |_, _| {
+            let (mut sender, body) = Body::channel();
+            tokio::spawn(async move {
+                for chunk in chunks {
+                    if sender.send_data((*chunk).into()).await.is_err() {
+                        return;
+                    }
+                    tokio::time::delay_for(std::time::Duration::from_millis(10)).await;
+                }
+            });
+            Response::new(body)
+        })
+    }
+
+    async fn streamed_lines(url: &str, grep: Option<&Regex>) -> Vec<String> {
+        let mut response = reqwest::Client::new().post(url).send().await.unwrap();
+        let mut lines = vec![];
+        stream_log_lines(&mut response, grep, |line| lines.push(line.to_string()))
+            .await
+            .unwrap();
+        lines
+    }
+
+    #[tokio::test]
+    async fn log_lines_split_between_chunks() {
+        let url = streaming(&["INFO sta", "rted\nWARN no pe", "ers\nINFO idle"]);
+        let lines = streamed_lines(&url, None).await;
+        assert_eq!(lines, ["INFO started", "WARN no peers", "INFO idle"]);
+    }
+
+    #[tokio::test]
+    async fn log_lines_filtered_by_grep() {
+        let url = streaming(&["INFO started\nWARN no", " peers\n", "INFO idle\n"]);
+        let grep = Regex::new("WARN|idle").unwrap();
+        let lines = streamed_lines(&url, Some(&grep)).await;
+        assert_eq!(lines, ["WARN no peers", "INFO idle"]);
+    }
+
+    /// auth server answering token requests with `replies` in order, the last one repeats
+    fn device_auth(interval: u64, expires_in: u64, replies: &'static [&'static str]) -> String {
+        let polls = std::sync::atomic::AtomicUsize::new(0);
+        stub::serve(move |path, _| match path {
+            "/login/device" => stub::json(json!({
+                "status": "ok",
+                "payload": {
+                    "device_code": "device-1",
+                    "user_code": "ABCD-1234",
+                    "verification_uri": "https://polkahub.local/device",
+                    "interval": interval,
+                    "expires_in": expires_in,
+                },
+            })),
+            _ => {
+                let poll = polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Response::new(Body::from(replies[poll.min(replies.len() - 1)]))
+            }
+        })
+    }
+
+    fn temp_home(name: &str) -> String {
+        let home = env::temp_dir().join(format!("polkahub-{}-{}", name, std::process::id()));
+        home.to_string_lossy().into_owned()
+    }
+
+    #[tokio::test]
+    async fn device_login_after_slow_down() {
+        let url = device_auth(
+            0,
+            60,
+            &[
+                r#"{"status":"pending"}"#,
+                r#"{"status":"slow_down"}"#,
+                r#"{"status":"ok","payload":{"token":"device-token"}}"#,
+            ],
+        );
+        let home = temp_home("device-login");
+        let _env = stub::env(&[
+            ("POLKAHUB_API_URL", &url),
+            ("POLKAHUB_HOME", &home),
+            ("POLKAHUB_TOKEN", ""),
+        ])
+        .await;
+        let started = Instant::now();
+        project(&["auth", "--device"]).login().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(5));
+        assert_eq!(read_token().unwrap(), "device-token");
+        std::fs::remove_dir_all(home).unwrap();
+    }
+
+    #[tokio::test]
+    async fn device_login_code_expires() {
+        let url = device_auth(1, 2, &[r#"{"status":"pending"}"#]);
+        let _env = stub::env(&[("POLKAHUB_API_URL", &url)]).await;
+        match PolkahubError::from(project(&["auth", "--device"]).login().await.unwrap_err()) {
+            PolkahubError::Auth(message) => assert!(message.contains("expired"), "{}", message),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn device_login_unexpected_response() {
+        let url = device_auth(0, 60, &["<html>Bad gateway</html>"]);
+        let _env = stub::env(&[("POLKAHUB_API_URL", &url)]).await;
+        match PolkahubError::from(project(&["auth", "--device"]).login().await.unwrap_err()) {
+            PolkahubError::Server { context, .. } => assert_eq!(context, "Unexpected response"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn chainspec_streamed_in_chunks() {
+        let url = stub::serve(|_, body| {
+            let accounts = body["balances"].as_array().map_or(0, Vec::len);
+            stub::json(json!({ "status": "ok", "payload": { "hash": accounts.to_string() } }))
+        });
+        let path = env::temp_dir().join(format!("polkahub-chainspec-{}.json", std::process::id()));
+        let balances: Vec<u64> = (0..50_000).collect();
+        std::fs::write(&path, json!({ "balances": balances }).to_string()).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 3 * UPLOAD_CHUNK_SIZE as u64);
+
+        let _env = stub::env(&[("POLKAHUB_API_URL", &url), ("POLKAHUB_TOKEN", TOKEN)]).await;
+        let chainspec = Chainspec {
+            version: "1".into(),
+            path: path.to_string_lossy().into_owned(),
+        };
+        let hash = project(&["publish"])
+            .upload_chainspec("myapp", "1.0.0", &chainspec)
+            .await
+            .unwrap();
+        assert_eq!(hash, "50000");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn cache_key_per_account() {
+        let body = json!({ "name": "myapp" });
+        assert_eq!(
+            cache_key(FIND_URL, TOKEN, &body),
+            "find-2d987a44b8fe6d13cda754f0881bee7496ceb4a6b6cdc513df371002e54fa2a2"
+        );
+        assert_ne!(
+            cache_key(FIND_URL, TOKEN, &body),
+            cache_key(FIND_URL, "other", &body)
+        );
+        assert_ne!(
+            cache_key(FIND_URL, TOKEN, &body),
+            cache_key(INFO_URL, TOKEN, &body)
+        );
+    }
+
+    #[tokio::test]
+    async fn dev_server_flow() {
+        let (addr, server) = dev_server::serve(([127, 0, 0, 1], 0).into()).unwrap();
+        tokio::spawn(server);
+        let home = temp_home("dev-server");
+        let hub = format!("{}/project", home);
+        std::fs::create_dir_all(&hub).unwrap();
+        let parachain =
+            "[parachain]\nname = \"myapp\"\nversion = \"1.0.0\"\ndescription = \"test chain\"\n";
+        let chainspec = "[chainspec]\nversion = \"1\"\npath = \"chainspec.json\"\n";
+        std::fs::write(format!("{}/Hub.toml", hub), [parachain, chainspec].concat()).unwrap();
+        std::fs::write(format!("{}/chainspec.json", hub), r#"{"name": "myapp"}"#).unwrap();
+        let url = format!("http://{}", addr);
+        let _env = stub::env(&[
+            ("POLKAHUB_API_URL", &url),
+            ("POLKAHUB_HOME", &home),
+            ("POLKAHUB_TOKEN", ""),
+        ])
+        .await;
+
+        let cli = project(&["auth"]);
+        let (email, password) = ("alice@example.com", "correct horse");
+        cli.send_register_request(REGISTER_URL, email, password)
+            .await
+            .unwrap()
+            .handle()
+            .unwrap();
+        cli.send_login_request(LOGIN_URL, email, password)
+            .await
+            .unwrap()
+            .handle()
+            .unwrap();
+        project(&["create", "myapp", "-h", &hub])
+            .create()
+            .await
+            .unwrap();
+        project(&["publish", "-h", &hub]).publish().await.unwrap();
+        match PolkahubError::from(
+            project(&["publish", "-h", &hub])
+                .publish()
+                .await
+                .unwrap_err(),
+        ) {
+            PolkahubError::Input(message) => assert!(message.contains("already published")),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let found = project(&["find", "myapp"])
+            .send_search_request(FIND_URL, "myapp")
+            .await
+            .unwrap();
+        match found {
+            FoundResponse::OkResult { payload } => {
+                let versions: Vec<_> = payload
+                    .iter()
+                    .map(|p| format!("{}/{}@{}", p.login, p.name, p.version))
+                    .collect();
+                assert_eq!(versions, ["alice/myapp@1.0.0"]);
+            }
+            r => panic!("unexpected response: {:?}", r),
+        }
+        project(&["install", "alice/myapp@1.0.0", "-h", &hub])
+            .install()
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(home).unwrap();
+    }
+
+    #[tokio::test]
+    async fn dev_server_org_flow() {
+        let (addr, server) = dev_server::serve(([127, 0, 0, 1], 0).into()).unwrap();
+        tokio::spawn(server);
+        let home = temp_home("dev-server-org");
+        let hub = format!("{}/project", home);
+        std::fs::create_dir_all(&hub).unwrap();
+        let parachain =
+            "[parachain]\nname = \"myapp\"\nversion = \"1.0.0\"\ndescription = \"test chain\"\n";
+        std::fs::write(format!("{}/Hub.toml", hub), parachain).unwrap();
+        let url = format!("http://{}", addr);
+        let _env = stub::env(&[
+            ("POLKAHUB_API_URL", &url),
+            ("POLKAHUB_HOME", &home),
+            ("POLKAHUB_TOKEN", ""),
+        ])
+        .await;
+
+        let cli = project(&["auth"]);
+        let (email, password) = ("bob@example.com", "correct horse");
+        cli.send_register_request(REGISTER_URL, email, password)
+            .await
+            .unwrap()
+            .handle()
+            .unwrap();
+        cli.send_login_request(LOGIN_URL, email, password)
+            .await
+            .unwrap()
+            .handle()
+            .unwrap();
+        project(&["create", "myapp", "--org", "myorg", "-h", &hub])
+            .create()
+            .await
+            .unwrap();
+        project(&["publish", "--org", "myorg", "-h", &hub])
+            .publish()
+            .await
+            .unwrap();
+        match PolkahubError::from(
+            project(&["publish", "--org", "myorg", "-h", &hub])
+                .publish()
+                .await
+                .unwrap_err(),
+        ) {
+            PolkahubError::Input(message) => {
+                assert!(message.contains("of myorg/myapp project is already published"))
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // the personal namespace has no myapp project
+        assert!(project(&["publish", "-h", &hub]).publish().await.is_err());
+
+        project(&["install", "myorg/myapp@1.0.0", "-h", &hub])
+            .install()
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(home).unwrap();
+    }
+
+    /// api answering info requests with `version` of alice/myapp
+    fn project_info(version: Value) -> String {
+        stub::serve(move |_, _| {
+            stub::json(json!({
+                "status": "ok",
+                "payload": {
+                    "login": "alice",
+                    "name": "myapp",
+                    "description": null,
+                    "created_at": null,
+                    "repo_url": "git@polkahub.local:alice/myapp.git",
+                    "versions": [version.clone()],
+                    "downloads": 0,
+                    "deploys": 0,
+                    "deployments": [],
+                },
+            }))
+        })
+    }
+
+    async fn check_version(url: &str, args: &[&str]) -> Result<()> {
+        let _env = stub::env(&[("POLKAHUB_API_URL", url), ("POLKAHUB_TOKEN", TOKEN)]).await;
+        let metadata = parse_full_name_project("alice/myapp@1.0.0").unwrap();
+        project(args).check_not_yanked(&metadata).await
+    }
+
+    #[tokio::test]
+    async fn yanked_version_refused() {
+        let url = project_info(json!({ "version": "1.0.0", "yanked": true }));
+        let install = ["install", "alice/myapp@1.0.0"];
+        match PolkahubError::from(check_version(&url, &install).await.unwrap_err()) {
+            PolkahubError::Input(message) => assert!(message.contains("is yanked"), "{}", message),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let allowed = ["install", "alice/myapp@1.0.0", "--allow-yanked"];
+        check_version(&url, &allowed).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn deprecated_version_allowed() {
+        let url = project_info(json!({ "version": "1.0.0", "deprecated": "use 2.0.0" }));
+        check_version(&url, &["install", "alice/myapp@1.0.0"])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn version_check_errors() {
+        let missing = stub::serve(|_, _| {
+            let mut response =
+                stub::json(json!({ "status": "error", "reason": "no such project" }));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response
+        });
+        check_version(&missing, &["install", "alice/myapp@1.0.0"])
+            .await
+            .unwrap();
+        let failing = stub::serve(|_, _| {
+            stub::json(json!({ "status": "error", "reason": "database is down" }))
+        });
+        match PolkahubError::from(
+            check_version(&failing, &["install", "alice/myapp@1.0.0"])
+                .await
+                .unwrap_err(),
+        ) {
+            PolkahubError::Server { reason, .. } => assert_eq!(reason, "database is down"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejected_token() {
+        for status in &[StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let status = *status;
+            let url = stub::serve(move |_, _| {
+                let mut response =
+                    stub::json(json!({ "status": "error", "reason": "token expired" }));
+                *response.status_mut() = status;
+                response
+            });
+            match PolkahubError::from(
+                check_version(&url, &["install", "alice/myapp@1.0.0"])
+                    .await
+                    .unwrap_err(),
+            ) {
+                PolkahubError::Auth(message) => assert!(message.contains(status.as_str())),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    /// api answering with an ETag and 304 when it is sent back,
+    /// returns the url and the If-None-Match headers of the requests
+    fn etag_server(reply: Value) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let requests = seen.clone();
+        let url = stub::serve_with_headers(move |headers, _, _| {
+            let etag = headers
+                .get(header::IF_NONE_MATCH)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            requests.lock().unwrap().push(etag.clone());
+            if etag.as_deref() == Some("\"v1\"") {
+                let mut response = Response::new(Body::empty());
+                *response.status_mut() = StatusCode::NOT_MODIFIED;
+                return response;
+            }
+            let mut response = stub::json(reply.clone());
+            response
+                .headers_mut()
+                .insert(header::ETAG, "\"v1\"".parse().unwrap());
+            response
+        });
+        (url, seen)
+    }
+
+    async fn cached_find(args: &[&str]) -> Result<String> {
+        project(args)
+            .post_request_cached(FIND_URL, json!({ "name": "myapp" }))
+            .await
+    }
+
+    #[tokio::test]
+    async fn cached_response_revalidated() {
+        let reply = json!({ "status": "ok", "payload": [] });
+        let (url, seen) = etag_server(reply.clone());
+        let home = temp_home("etag");
+        let _env = stub::env(&[
+            ("POLKAHUB_API_URL", &url),
+            ("POLKAHUB_HOME", &home),
+            ("POLKAHUB_TOKEN", TOKEN),
+        ])
+        .await;
+
+        let fetched = cached_find(&["find"]).await.unwrap();
+        let revalidated = cached_find(&["find"]).await.unwrap();
+        assert_eq!(fetched, reply.to_string());
+        assert_eq!(revalidated, fetched);
+        assert_eq!(*seen.lock().unwrap(), [None, Some("\"v1\"".to_string())]);
+        std::fs::remove_dir_all(home).unwrap();
+    }
+
+    #[tokio::test]
+    async fn offline_cache_hit_and_miss() {
+        let reply = json!({ "status": "ok", "payload": [] });
+        let (url, seen) = etag_server(reply.clone());
+        let home = temp_home("offline");
+        let _env = stub::env(&[
+            ("POLKAHUB_API_URL", &url),
+            ("POLKAHUB_HOME", &home),
+            ("POLKAHUB_TOKEN", TOKEN),
+        ])
+        .await;
+
+        match PolkahubError::from(cached_find(&["find", "--offline"]).await.unwrap_err()) {
+            PolkahubError::Network(message) => assert!(message.contains("Nothing cached")),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        cached_find(&["find"]).await.unwrap();
+        let offline = cached_find(&["find", "--offline"]).await.unwrap();
+        assert_eq!(offline, reply.to_string());
+        assert_eq!(seen.lock().unwrap().len(), 1);
+        std::fs::remove_dir_all(home).unwrap();
+    }
+
+    #[tokio::test]
+    async fn error_responses_not_cached() {
+        let reply = json!({ "status": "error", "reason": "database is down" });
+        let (url, _) = etag_server(reply.clone());
+        let home = temp_home("uncached");
+        let _env = stub::env(&[
+            ("POLKAHUB_API_URL", &url),
+            ("POLKAHUB_HOME", &home),
+            ("POLKAHUB_TOKEN", TOKEN),
+        ])
+        .await;
+
+        assert_eq!(cached_find(&["find"]).await.unwrap(), reply.to_string());
+        assert!(cached_find(&["find", "--offline"]).await.is_err());
+        let _ = std::fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn invalid_action() {
+        match project(&["logn"]).parse_action() {
+            Action::InputError(PolkahubError::Input(message)) => {
+                assert_eq!(message, "logn - is invalid action.")
+            }
+            a => panic!("unexpected action: {:?}", a),
+        }
+    }
+
+    #[test]
+    fn versions_in_semver_order() {
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0-alpha", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-alpha", "1.0.0-beta"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("2.0.0", "2.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn non_semver_versions_by_parts() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("2020.1", "2019.12"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+    }
+
+    fn found(versions: &[(&str, &str, Option<&str>)]) -> FoundResponse {
+        let payload: Vec<Value> = versions
+            .iter()
+            .map(|(name, version, published_at)| {
+                json!({
+                    "login": "alice",
+                    "name": name,
+                    "version": version,
+                    "published_at": published_at,
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({ "status": "ok", "payload": payload })).unwrap()
+    }
+
+    fn sorted(mut response: FoundResponse, sort: &str) -> Vec<String> {
+        response.sort(sort);
+        match response {
+            FoundResponse::OkResult { payload } => payload
+                .iter()
+                .map(|p| format!("{}@{}", p.name, p.version))
+                .collect(),
+            r => panic!("unexpected response: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn found_versions_sorted() {
+        let response = || {
+            found(&[
+                ("chain", "1.9.0", Some("2020-03-01T10:00:00Z")),
+                ("chain", "1.10.0", Some("2020-02-01T10:00:00Z")),
+                ("app", "0.1.0", None),
+                ("chain", "1.10.0-rc.1", Some("2020-04-01T10:00:00Z")),
+            ])
+        };
+        assert_eq!(
+            sorted(response(), "name"),
+            [
+                "app@0.1.0",
+                "chain@1.9.0",
+                "chain@1.10.0-rc.1",
+                "chain@1.10.0"
+            ]
+        );
+        assert_eq!(
+            sorted(response(), "version"),
+            [
+                "chain@1.10.0",
+                "chain@1.10.0-rc.1",
+                "chain@1.9.0",
+                "app@0.1.0"
+            ]
+        );
+        assert_eq!(
+            sorted(response(), "date"),
+            [
+                "chain@1.10.0-rc.1",
+                "chain@1.9.0",
+                "chain@1.10.0",
+                "app@0.1.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn cells_truncated_to_width() {
+        assert_eq!(truncate("short".into(), 10), "short");
+        assert_eq!(truncate("exactly10!".into(), 10), "exactly10!");
+        assert_eq!(truncate("a long description".into(), 10), "a long de…");
+        assert_eq!(truncate("описание проекта".into(), 9), "описание…");
+        assert_eq!(truncate("abc".into(), 0), "…");
+    }
+
+    #[test]
+    fn valid_emails() {
+        for email in &[
+            "alice@example.com",
+            "a.b+tag@mail.example.org",
+            "user@xn--e1afmkfd.xn--p1ai",
+        ] {
+            assert!(check_email(email).is_ok(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn invalid_emails() {
+        let long_local = format!("{}@example.com", "a".repeat(MAX_EMAIL_LOCAL_LENGTH + 1));
+        let long = format!("a@{}.com", "b".repeat(MAX_EMAIL_LENGTH));
+        for email in &[
+            "alice",
+            "alice@",
+            "@example.com",
+            "alice@example",
+            "alice@example.c",
+            "alice@-example.com",
+            "al..ice@example.com",
+            &long_local,
+            &long,
+        ] {
+            assert!(check_email(email).is_err(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn password_length_in_chars() {
+        assert!(check_password_length("1234567").is_err());
+        assert!(check_password_length("12345678").is_ok());
+        assert!(check_password_length(&"a".repeat(MAX_PASSWORD_LENGTH + 1)).is_err());
+        // 7 characters, 14 bytes
+        assert!(check_password_length("пароль1").is_err());
+        // 50 characters, 100 bytes
+        assert!(check_password_length(&"я".repeat(MAX_PASSWORD_LENGTH)).is_ok());
+    }
+
+    #[test]
+    fn lifetime_in_seconds() {
+        assert_eq!(parse_lifetime("12h").unwrap(), 12 * 3600);
+        assert_eq!(parse_lifetime("4w").unwrap(), 4 * 7 * 86400);
+        assert!(parse_lifetime("0d").is_err());
+        assert!(parse_lifetime("30y").is_err());
+        assert!(parse_lifetime(&format!("{}w", u64::MAX / 2)).is_err());
+    }
+
+    #[tokio::test]
+    async fn logs_json_error() {
+        let url = stub::serve(|_, _| {
+            let mut response =
+                stub::json(json!({ "status": "error", "reason": "app myapp is stopped" }));
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("application/json"),
+            );
+            response
+        });
+        let _env = stub::env(&[("POLKAHUB_API_URL", &url), ("POLKAHUB_TOKEN", TOKEN)]).await;
+        let e = project(&["logs", "myapp"]).logs().await.unwrap_err();
+        match PolkahubError::from(e) {
+            PolkahubError::Server { context, reason } => {
+                assert_eq!(context, "Could not get logs");
+                assert_eq!(reason, "app myapp is stopped");
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Password strength estimation shown while choosing a new password
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Level {
+    VeryWeak,
+    Weak,
+    Reasonable,
+    Strong,
+    VeryStrong,
+}
+
+#[derive(Debug)]
+pub struct Strength {
+    /// estimated entropy in bits
+    pub bits: f64,
+    pub level: Level,
+    pub hints: Vec<&'static str>,
+}
+
+impl Level {
+    fn from_bits(bits: f64) -> Level {
+        if bits < 28.0 {
+            Level::VeryWeak
+        } else if bits < 45.0 {
+            Level::Weak
+        } else if bits < 64.0 {
+            Level::Reasonable
+        } else if bits < 128.0 {
+            Level::Strong
+        } else {
+            Level::VeryStrong
+        }
+    }
+
+    /// number of filled cells in 5 cell meter
+    pub fn score(self) -> usize {
+        self as usize + 1
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::VeryWeak => "very weak",
+            Level::Weak => "weak",
+            Level::Reasonable => "reasonable",
+            Level::Strong => "strong",
+            Level::VeryStrong => "very strong",
+        }
+    }
+}
+
+/// Estimate entropy as effective length * log2(size of used character classes).
+/// Repeated characters count only partially to the effective length.
+pub fn estimate(password: &str) -> Strength {
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) =
+        (false, false, false, false, false);
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii_punctuation() || c == ' ' => symbol = true,
+            _ => other = true,
+        }
+    }
+    let pool = [
+        (lower, 26),
+        (upper, 26),
+        (digit, 10),
+        (symbol, 33),
+        (other, 100),
+    ]
+    .iter()
+    .filter(|(used, _)| *used)
+    .map(|(_, size)| size)
+    .sum::<u32>();
+    let length = password.chars().count();
+    let unique = password.chars().collect::<HashSet<_>>().len();
+    let effective_length = length.min(unique * 2);
+    let bits = if pool > 0 {
+        effective_length as f64 * f64::from(pool).log2()
+    } else {
+        0.0
+    };
+    let level = Level::from_bits(bits);
+
+    let mut hints = vec![];
+    if level < Level::Strong {
+        if length < 12 {
+            hints.push("use 12 or more characters");
+        }
+        if effective_length < length {
+            hints.push("avoid repeating characters");
+        }
+        if !upper || !lower {
+            hints.push("mix lowercase and uppercase letters");
+        }
+        if !digit {
+            hints.push("add digits");
+        }
+        if !symbol {
+            hints.push("add symbols");
+        }
+    }
+    Strength { bits, level, hints }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_password() {
+        let strength = estimate("");
+        assert_eq!(strength.bits, 0.0);
+        assert_eq!(strength.level, Level::VeryWeak);
+    }
+
+    #[test]
+    fn levels_grow_with_length_and_classes() {
+        assert_eq!(estimate("abcde").level, Level::VeryWeak);
+        assert_eq!(estimate("abcdefgh").level, Level::Weak);
+        assert_eq!(estimate("Abcdefgh12").level, Level::Reasonable);
+        assert_eq!(estimate("Abcdefgh12!?xyz").level, Level::Strong);
+        assert_eq!(
+            estimate("Abcdefgh12!?xyzAbcdefgh12!?").level,
+            Level::VeryStrong
+        );
+    }
+
+    #[test]
+    fn repeated_characters_count_partially() {
+        let repeated = estimate("aaaaaaaaaaaaaaaa");
+        assert_eq!(repeated.bits, 2.0 * 26f64.log2());
+        assert!(repeated.hints.contains(&"avoid repeating characters"));
+    }
+
+    #[test]
+    fn no_hints_for_strong_passwords() {
+        let strength = estimate("correct Horse battery 9 staple");
+        assert!(strength.level >= Level::Strong);
+        assert!(strength.hints.is_empty());
+    }
+}
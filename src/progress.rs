@@ -0,0 +1,66 @@
+//! Progress of long running requests on stderr: named stages and upload bars
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+const TICK_MS: u64 = 120;
+
+/// hide all progress output, e.g. when stderr is not a terminal
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn new_bar(len: Option<u64>) -> ProgressBar {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return ProgressBar::hidden();
+    }
+    match len {
+        Some(len) => ProgressBar::new(len),
+        None => ProgressBar::new_spinner(),
+    }
+}
+
+/// spinner with stage text and elapsed time, cleared on drop
+pub struct Stage(ProgressBar);
+
+impl Stage {
+    pub fn start(msg: &str) -> Stage {
+        let bar = new_bar(None);
+        bar.set_style(ProgressStyle::default_spinner().template("{spinner} {msg} {elapsed}"));
+        bar.set_message(msg);
+        bar.enable_steady_tick(TICK_MS);
+        Stage(bar)
+    }
+}
+
+impl Drop for Stage {
+    fn drop(&mut self) {
+        self.0.finish_and_clear();
+    }
+}
+
+/// determinate bar for uploads of a known size, clones share the bar until `finish`
+#[derive(Clone)]
+pub struct Upload(ProgressBar);
+
+impl Upload {
+    pub fn start(msg: &str, total_bytes: u64) -> Upload {
+        let bar = new_bar(Some(total_bytes));
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template("{msg} [{bar:30}] {bytes}/{total_bytes} {elapsed}")
+                .progress_chars("=> "),
+        );
+        bar.set_message(msg);
+        Upload(bar)
+    }
+
+    pub fn inc(&self, bytes: u64) {
+        self.0.inc(bytes);
+    }
+
+    pub fn finish(&self) {
+        self.0.finish_and_clear();
+    }
+}
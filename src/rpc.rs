@@ -0,0 +1,154 @@
+//! Substrate JSON-RPC calls used to check deployed node health
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    pub peers: u64,
+    pub is_syncing: bool,
+    pub should_have_peers: bool,
+}
+
+#[derive(Debug)]
+pub struct NodeSummary {
+    pub chain: String,
+    pub health: Health,
+    pub elapsed: Duration,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl Health {
+    /// node is connected to the network or is not expected to have peers (dev chain)
+    pub fn is_ready(&self) -> bool {
+        self.peers > 0 || !self.should_have_peers
+    }
+}
+
+async fn call<T: DeserializeOwned>(client: &reqwest::Client, url: &str, method: &str) -> Result<T> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": [],
+    });
+    let response: RpcResponse<T> = client.post(url).json(&body).send().await?.json().await?;
+    match (response.result, response.error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(e)) => Err(anyhow!("{} failed: {} ({})", method, e.message, e.code)),
+        (None, None) => Err(anyhow!("{} returned empty response", method)),
+    }
+}
+
+pub async fn system_health(client: &reqwest::Client, url: &str) -> Result<Health> {
+    call(client, url, "system_health").await
+}
+
+pub async fn system_chain(client: &reqwest::Client, url: &str) -> Result<String> {
+    call(client, url, "system_chain").await
+}
+
+/// Poll node http endpoint until it reports ready health state or timeout expires
+pub async fn wait_for_ready(
+    url: &str,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<NodeSummary> {
+    let client = reqwest::Client::builder().timeout(interval).build()?;
+    let started = Instant::now();
+    let mut last_error = anyhow!("node did not respond");
+    while started.elapsed() < timeout {
+        match system_health(&client, url).await {
+            Ok(health) if health.is_ready() => {
+                let chain = system_chain(&client, url).await?;
+                return Ok(NodeSummary {
+                    chain,
+                    health,
+                    elapsed: started.elapsed(),
+                });
+            }
+            Ok(health) => {
+                last_error = anyhow!("node is up but has {} peers", health.peers);
+            }
+            Err(e) => last_error = e,
+        }
+        delay_for(interval).await;
+    }
+    Err(anyhow!(
+        "node at {} is not ready after {}s: {}",
+        url,
+        timeout.as_secs(),
+        last_error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+    const INTERVAL: Duration = Duration::from_millis(20);
+
+    fn health(peers: u64) -> Value {
+        json!({ "peers": peers, "isSyncing": false, "shouldHavePeers": true })
+    }
+
+    /// node answering `system_health` with `health_for(call number)`
+    fn node<F>(health_for: F) -> String
+    where
+        F: Fn(usize) -> Value + Send + Sync + 'static,
+    {
+        let calls = AtomicUsize::new(0);
+        stub::serve(move |_, body| match body["method"].as_str() {
+            Some("system_chain") => stub::json(json!({ "result": "Development" })),
+            _ => stub::json(health_for(calls.fetch_add(1, Ordering::SeqCst))),
+        })
+    }
+
+    #[tokio::test]
+    async fn ready_node() {
+        let url = node(|_| json!({ "result": health(3) }));
+        let summary = wait_for_ready(&url, TIMEOUT, INTERVAL).await.unwrap();
+        assert_eq!(summary.chain, "Development");
+        assert_eq!(summary.health.peers, 3);
+    }
+
+    #[tokio::test]
+    async fn waits_for_peers() {
+        let url = node(|call| json!({ "result": health(if call < 2 { 0 } else { 1 }) }));
+        let summary = wait_for_ready(&url, TIMEOUT, INTERVAL).await.unwrap();
+        assert_eq!(summary.health.peers, 1);
+    }
+
+    #[tokio::test]
+    async fn rpc_error_is_reported() {
+        let url = node(|_| json!({ "error": { "code": -32601, "message": "Method not found" } }));
+        let e = wait_for_ready(&url, TIMEOUT, INTERVAL).await.unwrap_err();
+        assert!(e.to_string().contains("Method not found (-32601)"), "{}", e);
+    }
+
+    #[tokio::test]
+    async fn times_out_without_peers() {
+        let url = node(|_| json!({ "result": health(0) }));
+        let e = wait_for_ready(&url, TIMEOUT, INTERVAL).await.unwrap_err();
+        assert!(e.to_string().contains("has 0 peers"), "{}", e);
+    }
+}
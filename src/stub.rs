@@ -0,0 +1,86 @@
+//! Stub http server for tests of api and node requests
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, HeaderMap, Request, Response, Server,
+};
+use serde_json::Value;
+use std::{convert::Infallible, env, ffi::OsString, sync::Arc};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// serve `handler` on a free local port, returns the base url
+pub fn serve<F>(handler: F) -> String
+where
+    F: Fn(&str, Value) -> Response<Body> + Send + Sync + 'static,
+{
+    serve_with_headers(move |_, path, body| handler(path, body))
+}
+
+/// like `serve`, the handler also gets headers of the request
+pub fn serve_with_headers<F>(handler: F) -> String
+where
+    F: Fn(&HeaderMap, &str, Value) -> Response<Body> + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    let make_service = make_service_fn(move |_| {
+        let handler = handler.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let handler = handler.clone();
+                async move {
+                    let path = request.uri().path().to_string();
+                    let headers = request.headers().clone();
+                    let bytes = hyper::body::to_bytes(request.into_body())
+                        .await
+                        .unwrap_or_default();
+                    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+                    Ok::<_, Infallible>(handler(&headers, &path, body))
+                }
+            }))
+        }
+    });
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    url
+}
+
+/// response with json `value` as body
+pub fn json(value: Value) -> Response<Body> {
+    Response::new(Body::from(value.to_string()))
+}
+
+lazy_static::lazy_static! {
+    static ref ENV: Mutex<()> = Mutex::new(());
+}
+
+/// environment variables changed by a test, restored on drop
+pub struct Env {
+    saved: Vec<(String, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for Env {
+    fn drop(&mut self) {
+        for (key, value) in &self.saved {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
+        }
+    }
+}
+
+/// set environment variables for the duration of a test,
+/// tests reading them are serialized by the returned guard
+pub async fn env(vars: &[(&str, &str)]) -> Env {
+    let lock = ENV.lock().await;
+    let saved = vars
+        .iter()
+        .map(|(key, value)| {
+            let previous = env::var_os(key);
+            env::set_var(key, value);
+            (key.to_string(), previous)
+        })
+        .collect();
+    Env { saved, _lock: lock }
+}